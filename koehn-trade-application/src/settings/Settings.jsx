@@ -10,6 +10,10 @@ const getInputType = (type) => {
         return 'number';
     }
 
+    if (type === 'boolean') {
+        return 'checkbox';
+    }
+
     return 'text';
 };
 
@@ -131,10 +135,23 @@ class Settings extends React.Component {
             const path = field.Path;
             const value = objectPath.get(this.state.config, path);
 
+            if (fieldType === 'select') {
+                html.push((
+                    <>
+                        <label key={`label-${path}`} type="text" className={`label-${level}`} htmlFor={currentFieldName} path={path}>{label}</label>
+                        <select key={path} path={path} onChange={this.onSettingChange.bind(this)} value={value} fieldtype={fieldType}>
+                            {field.Options.map((option) => <option key={option} value={option}>{option}</option>)}
+                        </select>
+                        <SettingsError errors={this.state.validationErrors} path={path} className=".error" />
+                    </>
+                ));
+                return;
+            }
+
             html.push((
                 <>
                     <label key={`label-${path}`} type="text" className={`label-${level}`} htmlFor={currentFieldName} path={path}>{label}</label>
-                    <input key={path} type={getInputType(fieldType)} path={path} onChange={this.onSettingChange.bind(this)} value={value} fieldtype={fieldType} />
+                    <input key={path} type={getInputType(fieldType)} path={path} onChange={this.onSettingChange.bind(this)} value={value} checked={fieldType === 'boolean' ? value : undefined} fieldtype={fieldType} />
                     <SettingsError errors={this.state.validationErrors} path={path} className=".error" />
                 </>
             ));
@@ -175,6 +192,10 @@ class Settings extends React.Component {
         const getValue = (type, fieldType) => {
             const value = event.target.value;
 
+            if (type === 'checkbox') {
+                return event.target.checked;
+            }
+
             if (type === 'number') {
                 if (value === '') {
                     return 0;
@@ -3,15 +3,24 @@ import ReactJson from 'react-json-view';
 import PurchaseHistory from './Graphs/PurchaseHistory';
 import ValueHistory from './Graphs/ValueHistory';
 
+// disqualified bots are only picked when there is nothing else
+function betterBot(botOne, botTwo) {
+    if (botOne.disqualified !== botTwo.disqualified) {
+        return botOne.disqualified ? botTwo : botOne;
+    }
+
+    return botOne.fitness > botTwo.fitness ?
+        botOne :
+        botTwo;
+}
+
 function findBestBotInGeneration(generation) {
-    return generation.reduce((bestBot, bot) => {
+    return generation.bots.reduce((bestBot, bot) => {
         if (!bestBot) {
             return bot;
         }
 
-        return bestBot.fitness > bot.fitness ?
-            bestBot :
-            bot;
+        return betterBot(bestBot, bot);
     }, undefined);
 }
 
@@ -23,9 +32,7 @@ function findBestBot(generations) {
             return bestBotInGeneration;
         }
 
-        return bestBot.fitness > bestBotInGeneration.fitness ?
-            bestBot :
-            bestBotInGeneration;
+        return betterBot(bestBot, bestBotInGeneration);
     }, undefined);
 }
 
@@ -65,7 +72,7 @@ function calculateBotStats(bestBot) {
     };
 }
 
-function renderBotStats(runningSimulation, bestBot) {
+function renderBotStats(runningSimulation, bestBot, baseline) {
     if (runningSimulation) {
         return (
             <></>
@@ -76,7 +83,7 @@ function renderBotStats(runningSimulation, bestBot) {
         <>
             <PurchaseHistory bot={bestBot} />
             <ReactJson src={calculateBotStats(bestBot)} theme="monokai" />
-            <ValueHistory bot={bestBot} />
+            <ValueHistory bot={bestBot} baseline={baseline} />
         </>
     );
 }
@@ -96,7 +103,7 @@ function BestBot(props) {
     return (
         <>
             <ReactJson src={bestBotSimple} theme="monokai" />
-            {renderBotStats(props.runningSimulation, bestBot)}
+            {renderBotStats(props.runningSimulation, bestBot, props.generations[0].baseline)}
         </>
     );
 }
@@ -1,13 +1,15 @@
 import React from 'react';
 import { LineChart, Line, CartesianGrid, XAxis, YAxis } from 'recharts';
 
+// disqualified bots are left out, a generation where every bot was disqualified has no average
 function buildData(generations) {
     return generations.map((generation, index) => {
-        const totalFitness = generation.reduce((total, bot) => total + bot.fitness, 0);
+        const qualifiedBots = generation.bots.filter((bot) => !bot.disqualified);
+        const totalFitness = qualifiedBots.reduce((total, bot) => total + bot.fitness, 0);
 
         return {
             name: index + 1,
-            amt: totalFitness / generation.length
+            amt: qualifiedBots.length > 0 ? totalFitness / qualifiedBots.length : null
         };
     });
 }
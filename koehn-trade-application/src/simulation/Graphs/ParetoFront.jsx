@@ -0,0 +1,59 @@
+import React from 'react';
+import { ScatterChart, Scatter, CartesianGrid, XAxis, YAxis, Tooltip } from 'recharts';
+
+// how each NSGA-II objective is read off a bot
+const OBJECTIVES = {
+    Return: { name: 'Return', unit: '%', value: (bot) => bot.metrics.totalReturn * 100 },
+    MaxDrawdown: { name: 'Max Drawdown', unit: '%', value: (bot) => bot.metrics.maxDrawdown * 100 },
+    TradeCount: { name: 'Trades', unit: '', value: (bot) => bot.metrics.numberOfTrades },
+    Fitness: { name: 'Fitness', unit: '', value: (bot) => bot.fitness }
+};
+
+// the first two objectives the front was found on, a single objective is plotted against fitness
+function chartObjectives(generation) {
+    const objectives = generation.objectives || ['Return', 'MaxDrawdown'];
+    if (objectives.length > 1) {
+        return objectives.slice(0, 2);
+    }
+
+    return objectives[0] === 'Fitness' ? ['Fitness', 'Return'] : [objectives[0], 'Fitness'];
+}
+
+// the bots on the pareto front of the latest generation
+function buildData(generation, [yObjective, xObjective]) {
+    const paretoFront = new Set(generation.paretoFront);
+
+    return generation.bots
+        .filter((bot) => paretoFront.has(bot.id))
+        .map((bot) => {
+            return {
+                id: bot.id,
+                x: OBJECTIVES[xObjective].value(bot),
+                y: OBJECTIVES[yObjective].value(bot)
+            };
+        });
+}
+
+function ParetoFront(props) {
+    if (props.generations.length === 0) {
+        return (
+            <></>
+        );
+    }
+
+    const generation = props.generations[props.generations.length - 1];
+    const [yObjective, xObjective] = chartObjectives(generation);
+    const data = buildData(generation, [yObjective, xObjective]);
+
+    return (
+        <ScatterChart width={1000} height={300}>
+            <Scatter data={data} fill="#8884d8" />
+            <CartesianGrid stroke="#ccc" />
+            <XAxis type="number" dataKey="x" name={OBJECTIVES[xObjective].name} unit={OBJECTIVES[xObjective].unit} />
+            <YAxis type="number" dataKey="y" name={OBJECTIVES[yObjective].name} unit={OBJECTIVES[yObjective].unit} />
+            <Tooltip />
+        </ScatterChart>
+    );
+}
+
+export default ParetoFront;
@@ -2,11 +2,17 @@ import React from 'react';
 import { LineChart, Line, CartesianGrid, XAxis, YAxis, Legend, Tooltip } from 'recharts';
 import { DateTime } from 'luxon';
 
-function buildData(bot) {
+// the buy and hold baseline is shown at the same times as the bot's value
+function buildData(bot, baseline) {
+    const baselineValues = new Map((baseline ? baseline.history : []).map((baselineValue) => [baselineValue.time, baselineValue.value]));
+
     const valueHistory = bot.valueHistory;
     return valueHistory.map((history) => {
         return {
             value: history.value,
+            cash: history.cash,
+            positionValue: history.positionValue,
+            baseline: baselineValues.get(history.time),
             date: DateTime.fromSeconds(history.time).toFormat('yyyy-MM-dd-hh-mm')
         };
     });
@@ -19,11 +25,14 @@ function ValueHistory(props) {
         );
     }
 
-    const data = buildData(props.bot);
+    const data = buildData(props.bot, props.baseline);
 
     return (
         <LineChart width={1000} height={300} data={data}>
             <Line type="monotone" dataKey="value" stroke="#8884d8" />
+            <Line type="monotone" dataKey="cash" stroke="#82ca9d" />
+            <Line type="monotone" dataKey="positionValue" stroke="#ffc658" />
+            <Line type="monotone" dataKey="baseline" stroke="#ff7300" />
             <CartesianGrid stroke="#ccc" />
             <XAxis dataKey="date" />
             <YAxis />
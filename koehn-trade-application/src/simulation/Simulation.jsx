@@ -2,6 +2,7 @@ import React from 'react';
 import fetch from 'node-fetch';
 import Poll from './Poll';
 import AverageFitness from './Graphs/AverageFitness';
+import ParetoFront from './Graphs/ParetoFront';
 import BestBot from './BestBot';
 import './Simulation.css';
 
@@ -89,6 +90,7 @@ class Simulation extends React.Component {
                         generations={this.state.generations}
                     />
                     <AverageFitness generations={this.state.generations} />
+                    <ParetoFront generations={this.state.generations} />
                     <div className="bot">
                         <BestBot generations={this.state.generations} runningSimulation={this.state.runningSimulation} />
                     </div>
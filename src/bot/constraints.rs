@@ -0,0 +1,90 @@
+use crate::bot::fitness::Metrics;
+use crate::config::ConfigError;
+
+// Why a bot broke the activity constraints
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ConstraintViolation {
+    TooFewTrades,
+    TooMuchExposure,
+    TooMuchDrawdown
+}
+
+// Disqualified bots are given a fitness of 0 but are sorted after every other bot and left out of breeding, the rankings and the fitness stats
+// a penalty is taken off the fitness for every time over the constraint was broken, e.g. half the minimum trades is a penalty of 0.5 * Penalty
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum ViolationAction {
+    #[default]
+    Disqualify,
+    Penalty
+}
+
+// Stops bots that made one lucky trade or took on too much risk from scoring as well as the rest
+// Every constraint is off unless it's set
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActivityConstraints {
+    #[serde(default)]
+    pub minimum_trades: Option<u64>,
+    #[serde(default)]
+    pub maximum_exposure: Option<f64>, // the fraction of periods a position can be held for
+    #[serde(default)]
+    pub maximum_drawdown: Option<f64>,
+    #[serde(default)]
+    pub action: ViolationAction,
+    #[serde(default)]
+    pub penalty: f64
+}
+
+// how far over a limit a value is as a fraction of the limit
+fn breach(value: f64, limit: f64) -> f64 {
+    match limit > 0.0 {
+        true => (value - limit) / limit,
+        false => value
+    }
+}
+
+impl ActivityConstraints {
+    // the constraints that were broken along with how badly
+    pub fn check(&self, metrics: &Metrics) -> Vec<(ConstraintViolation, f64)> {
+        let mut violations = Vec::<(ConstraintViolation, f64)>::new();
+
+        if let Some(minimum_trades) = self.minimum_trades {
+            if metrics.number_of_trades < minimum_trades {
+                let shortfall = (minimum_trades - metrics.number_of_trades) as f64 / minimum_trades as f64;
+                violations.push((ConstraintViolation::TooFewTrades, shortfall));
+            }
+        }
+
+        if let Some(maximum_exposure) = self.maximum_exposure {
+            if metrics.exposure > maximum_exposure {
+                violations.push((ConstraintViolation::TooMuchExposure, breach(metrics.exposure, maximum_exposure)));
+            }
+        }
+
+        if let Some(maximum_drawdown) = self.maximum_drawdown {
+            if metrics.max_drawdown > maximum_drawdown {
+                violations.push((ConstraintViolation::TooMuchDrawdown, breach(metrics.max_drawdown, maximum_drawdown)));
+            }
+        }
+
+        violations
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.maximum_exposure.is_some_and(|maximum_exposure| !(0.0..=1.0).contains(&maximum_exposure)) {
+            config_errors.push(ConfigError::new("Maximum Exposure must be between 0 and 1".to_string(), "ActivityConstraints.MaximumExposure".to_string()));
+        }
+
+        if self.maximum_drawdown.is_some_and(|maximum_drawdown| !(0.0..=1.0).contains(&maximum_drawdown)) {
+            config_errors.push(ConfigError::new("Maximum Drawdown must be between 0 and 1".to_string(), "ActivityConstraints.MaximumDrawdown".to_string()));
+        }
+
+        if self.penalty < 0.0 {
+            config_errors.push(ConfigError::new("Penalty can not be negative".to_string(), "ActivityConstraints.Penalty".to_string()));
+        }
+
+        config_errors
+    }
+}
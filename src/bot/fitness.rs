@@ -0,0 +1,309 @@
+use crate::bot::holdings::SoldHolding;
+use crate::config::ConfigError;
+
+const SECONDS_IN_A_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+// a ratio with nothing to divide by (no losses, no drawdown, no volatility) is capped instead of being infinite
+const MAXIMUM_RATIO: f64 = 100.0;
+
+// How bots are scored at the end of a run
+// FinalMoney is the original behaviour, the rest are worked out from the equity curve and the sold holdings
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum FitnessFunction {
+    #[default]
+    FinalMoney,
+    Sharpe,
+    Sortino,
+    Calmar,
+    ProfitFactor,
+    Cagr,
+    ReturnOverMaxDrawdown,
+    Alpha,
+    ExcessReturn,
+    InformationRatio,
+    Composite(Vec<WeightedFitnessFunction>) // weighted sum of the other functions
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WeightedFitnessFunction {
+    pub function: FitnessFunction,
+    pub weight: f64
+}
+
+// Everything the fitness functions need from a finished bot
+pub struct RunSummary<'a> {
+    pub equity: &'a [f64], // the value of the bot over the run, starting money first and final money last
+    pub sold_holdings: &'a [SoldHolding],
+    pub years: f64,
+    pub benchmark: Option<&'a [f64]>, // the buy and hold baseline at the same points as the equity
+    pub benchmark_return: Option<f64>, // what buying and holding made over the whole run
+    pub exposure: f64 // the fraction of the periods traded a position was held for
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        return numerator / denominator;
+    }
+
+    match numerator > 0.0 {
+        true => MAXIMUM_RATIO,
+        false => 0.0
+    }
+}
+
+// anything that isn't a number ranks as nothing and infinities are capped like the ratios so bots can always be sorted
+fn finite(value: f64) -> f64 {
+    if value.is_nan() {
+        return 0.0;
+    }
+
+    match value.is_infinite() {
+        true => value.signum() * MAXIMUM_RATIO,
+        false => value
+    }
+}
+
+// once the money is gone there is nothing left to make a return on
+fn calculate_returns(equity: &[f64]) -> Vec<f64> {
+    equity
+        .windows(2)
+        .map(|window| match window[0] > 0.0 {
+            true => (window[1] / window[0]) - 1.0,
+            false => 0.0
+        })
+        .collect()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn covariance(values_one: &[f64], values_two: &[f64]) -> f64 {
+    let average_one = mean(values_one);
+    let average_two = mean(values_two);
+
+    mean(&values_one.iter().zip(values_two).map(|(one, two)| (one - average_one) * (two - average_two)).collect::<Vec<f64>>())
+}
+
+fn standard_deviation(values: &[f64]) -> f64 {
+    covariance(values, values).sqrt()
+}
+
+// only the returns below zero count as risk
+fn downside_deviation(values: &[f64]) -> f64 {
+    let variance = mean(&values.iter().map(|value| value.min(0.0).powi(2)).collect::<Vec<f64>>());
+
+    variance.sqrt()
+}
+
+// returns are only annualised when we know how long the run was
+fn periods_per_year(summary: &RunSummary) -> f64 {
+    let number_of_returns = summary.equity.len().saturating_sub(1) as f64;
+
+    match summary.years > 0.0 {
+        true => number_of_returns / summary.years,
+        false => 1.0
+    }
+}
+
+pub fn years_between(start_time: Option<u64>, end_time: Option<u64>) -> f64 {
+    match (start_time, end_time) {
+        (Some(start_time), Some(end_time)) => end_time.saturating_sub(start_time) as f64 / SECONDS_IN_A_YEAR,
+        _ => 0.0
+    }
+}
+
+pub fn total_return(equity: &[f64]) -> f64 {
+    match (equity.first(), equity.last()) {
+        (Some(first), Some(last)) if *first > 0.0 => (last / first) - 1.0,
+        _ => 0.0
+    }
+}
+
+// the largest fall from a peak as a fraction of the peak
+pub fn calculate_max_drawdown(equity: &[f64]) -> f64 {
+    let mut peak = f64::MIN;
+    let mut max_drawdown = 0.0;
+
+    for value in equity {
+        peak = peak.max(*value);
+        if peak > 0.0 {
+            max_drawdown = f64::max(max_drawdown, (peak - value) / peak);
+        }
+    }
+
+    max_drawdown
+}
+
+pub fn calculate_sharpe(summary: &RunSummary) -> f64 {
+    let returns = calculate_returns(summary.equity);
+    ratio(mean(&returns), standard_deviation(&returns)) * periods_per_year(summary).sqrt()
+}
+
+pub fn calculate_sortino(summary: &RunSummary) -> f64 {
+    let returns = calculate_returns(summary.equity);
+    ratio(mean(&returns), downside_deviation(&returns)) * periods_per_year(summary).sqrt()
+}
+
+pub fn calculate_cagr(summary: &RunSummary) -> f64 {
+    let growth = total_return(summary.equity) + 1.0;
+    if summary.years <= 0.0 || growth <= 0.0 {
+        return total_return(summary.equity);
+    }
+
+    // a short run can compound past what a float can hold
+    (growth.powf(1.0 / summary.years) - 1.0).min(MAXIMUM_RATIO)
+}
+
+pub fn calculate_calmar(summary: &RunSummary) -> f64 {
+    ratio(calculate_cagr(summary), calculate_max_drawdown(summary.equity))
+}
+
+pub fn calculate_return_over_max_drawdown(summary: &RunSummary) -> f64 {
+    ratio(total_return(summary.equity), calculate_max_drawdown(summary.equity))
+}
+
+// money won on winning trades over money lost on losing ones
+pub fn calculate_profit_factor(summary: &RunSummary) -> f64 {
+    let gross_profit: f64 = summary.sold_holdings
+        .iter()
+        .filter(|holding| holding.amount_gained > 0.0)
+        .map(|holding| holding.amount_gained)
+        .sum();
+    let gross_loss: f64 = summary.sold_holdings
+        .iter()
+        .filter(|holding| holding.amount_gained < 0.0)
+        .map(|holding| -holding.amount_gained)
+        .sum();
+
+    ratio(gross_profit, gross_loss)
+}
+
+// how much more the bot made then holding the benchmark
+pub fn calculate_excess_return(summary: &RunSummary) -> f64 {
+    match summary.benchmark_return {
+        Some(benchmark_return) => total_return(summary.equity) - benchmark_return,
+        None => 0.0
+    }
+}
+
+// Jensen's alpha with no risk free rate, the return left over once the bot's exposure to the benchmark is taken out
+pub fn calculate_alpha(summary: &RunSummary) -> f64 {
+    let benchmark = match summary.benchmark {
+        Some(benchmark) => benchmark,
+        None => return 0.0
+    };
+
+    let returns = calculate_returns(summary.equity);
+    let benchmark_returns = calculate_returns(benchmark);
+
+    let benchmark_variance = covariance(&benchmark_returns, &benchmark_returns);
+    let beta = match benchmark_variance > 0.0 {
+        true => covariance(&returns, &benchmark_returns) / benchmark_variance,
+        false => 0.0
+    };
+
+    (mean(&returns) - (beta * mean(&benchmark_returns))) * periods_per_year(summary)
+}
+
+// how consistently the bot beat the benchmark, the mean over the spread of the returns above it
+pub fn calculate_information_ratio(summary: &RunSummary) -> f64 {
+    let benchmark = match summary.benchmark {
+        Some(benchmark) => benchmark,
+        None => return 0.0
+    };
+
+    let active_returns: Vec<f64> = calculate_returns(summary.equity)
+        .iter()
+        .zip(calculate_returns(benchmark))
+        .map(|(value, benchmark_value)| value - benchmark_value)
+        .collect();
+
+    ratio(mean(&active_returns), standard_deviation(&active_returns)) * periods_per_year(summary).sqrt()
+}
+
+// The raw numbers behind a run, kept on the bot so runs can be compared on more then one objective
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metrics {
+    pub total_return: f64, // as a fraction of the starting money
+    pub max_drawdown: f64,
+    pub number_of_trades: u64,
+    pub exposure: f64,
+    pub alpha: f64, // the benchmark metrics are 0 when there is no baseline to compare to
+    pub excess_return: f64,
+    pub information_ratio: f64
+}
+
+impl Metrics {
+    pub fn new(summary: &RunSummary) -> Metrics {
+        Metrics {
+            total_return: finite(total_return(summary.equity)),
+            max_drawdown: finite(calculate_max_drawdown(summary.equity)),
+            number_of_trades: summary.sold_holdings.len() as u64,
+            exposure: finite(summary.exposure),
+            alpha: finite(calculate_alpha(summary)),
+            excess_return: finite(calculate_excess_return(summary)),
+            information_ratio: finite(calculate_information_ratio(summary))
+        }
+    }
+}
+
+impl FitnessFunction {
+    pub fn calculate(&self, summary: &RunSummary) -> f64 {
+        let fitness = match self {
+            FitnessFunction::FinalMoney => summary.equity.last().copied().unwrap_or(0.0),
+            FitnessFunction::Sharpe => calculate_sharpe(summary),
+            FitnessFunction::Sortino => calculate_sortino(summary),
+            FitnessFunction::Calmar => calculate_calmar(summary),
+            FitnessFunction::ProfitFactor => calculate_profit_factor(summary),
+            FitnessFunction::Cagr => calculate_cagr(summary),
+            FitnessFunction::ReturnOverMaxDrawdown => calculate_return_over_max_drawdown(summary),
+            FitnessFunction::Alpha => calculate_alpha(summary),
+            FitnessFunction::ExcessReturn => calculate_excess_return(summary),
+            FitnessFunction::InformationRatio => calculate_information_ratio(summary),
+            FitnessFunction::Composite(weighted_functions) => weighted_functions
+                .iter()
+                .map(|weighted_function| weighted_function.weight * weighted_function.function.calculate(summary))
+                .sum()
+        };
+
+        finite(fitness)
+    }
+
+    // everything but final money, profit factor and excess return is worked out from the equity curve
+    fn needs_equity_curve(&self) -> bool {
+        match self {
+            FitnessFunction::FinalMoney | FitnessFunction::ProfitFactor | FitnessFunction::ExcessReturn => false,
+            FitnessFunction::Composite(weighted_functions) => weighted_functions
+                .iter()
+                .any(|weighted_function| weighted_function.function.needs_equity_curve()),
+            _ => true
+        }
+    }
+
+    pub fn validate(&self, equity_curve_enabled: bool) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.needs_equity_curve() && !equity_curve_enabled {
+            config_errors.push(ConfigError::new("Fitness Function needs the Equity Curve to be enabled".to_string(), "FitnessFunction".to_string()));
+        }
+
+        if let FitnessFunction::Composite(weighted_functions) = self {
+            if weighted_functions.is_empty() {
+                config_errors.push(ConfigError::new("Composite needs at least one function".to_string(), "FitnessFunction.Composite".to_string()));
+            }
+
+            if weighted_functions.iter().any(|weighted_function| !weighted_function.weight.is_finite()) {
+                config_errors.push(ConfigError::new("Weights must be numbers".to_string(), "FitnessFunction.Composite".to_string()));
+            }
+        }
+
+        config_errors
+    }
+}
@@ -1,5 +1,6 @@
 use crate::asset::Asset;
-use crate::bot::Traits;
+use crate::strategy::ExitRules;
+use crate::config::IntrabarOrdering;
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum SellReason {
@@ -8,13 +9,22 @@ pub enum SellReason {
     MaxPeriodsHeld,
     TargetedSellPrice,
     Forced,
+    Liquidated,
     None
 }
 
+// Long positions profit when the price goes up, short positions borrow the asset and profit when it goes down
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Side {
+    Long,
+    Short
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CurrentHolding {
     pub asset: Asset,
+    pub side: Side,
     pub purchase_time: u64,
     pub amount: f64,
     pub money_spent: f64,
@@ -23,45 +33,136 @@ pub struct CurrentHolding {
     pub trailing_stop_loss: f64, // current trailing price to stop out at
     pub periods_held: u64,
     pub buy_fee: f64,
-    pub targeted_sell_price: f64
+    pub buy_fee_tier: usize, // the fee schedule tier the buy fee was charged at
+    pub borrow_fee: f64, // total fee paid to borrow the asset of a short position
+    pub targeted_sell_price: f64,
+    pub partial_fill: bool, // the order that opened the holding ran out of volume before it completely filled
+    pub leverage: f64,
+    pub borrowed: f64, // money borrowed to open a leveraged position, paid back when it's sold
+    pub interest: f64 // total interest paid on the borrowed money
 }
 
-fn calculate_stop_loss(price: f64, stop_loss_percentage: f64) -> f64 {
-    price - (price * (stop_loss_percentage / 100.0))
+fn calculate_stop_loss(price: f64, stop_loss_percentage: f64, side: Side) -> f64 {
+    match side {
+        Side::Long => price - (price * (stop_loss_percentage / 100.0)),
+        Side::Short => price + (price * (stop_loss_percentage / 100.0))
+    }
 }
 
 fn calculate_percent_gained(money_spent: f64, money_from_sell: f64) -> f64 {
     ((money_from_sell - money_spent) / money_spent) * 100.0
 }
 
-fn calculate_targeted_sell_price(purchase_price: f64, target_sell_percentage: f64) -> f64 {
-    purchase_price + (purchase_price * (target_sell_percentage / 100.0))
+fn calculate_targeted_sell_price(purchase_price: f64, target_sell_percentage: f64, side: Side) -> f64 {
+    match side {
+        Side::Long => purchase_price + (purchase_price * (target_sell_percentage / 100.0)),
+        Side::Short => purchase_price - (purchase_price * (target_sell_percentage / 100.0))
+    }
 }
 
 impl CurrentHolding {
-    pub fn new(purchase_price: f64, purchase_time: u64, amount: f64, money_spent: f64, asset: Asset, traits: &Traits, buy_fee: f64) -> CurrentHolding {
+    // for a short position the money spent is the collateral put up for the borrowed asset
+    pub fn new(purchase_price: f64, purchase_time: u64, amount: f64, asset: Asset, side: Side, exit_rules: &ExitRules, buy_fee: f64) -> CurrentHolding {
         CurrentHolding {
             asset,
+            side,
             amount,
             purchase_time,
-            money_spent,
+            money_spent: (amount * purchase_price) + buy_fee,
             purchase_price,
-            stop_loss: calculate_stop_loss(purchase_price, traits.stop_loss),
-            trailing_stop_loss: calculate_stop_loss(purchase_price, traits.trailing_stop_loss),
+            stop_loss: calculate_stop_loss(purchase_price, exit_rules.stop_loss, side),
+            trailing_stop_loss: calculate_stop_loss(purchase_price, exit_rules.trailing_stop_loss, side),
             periods_held: 0,
             buy_fee,
-            targeted_sell_price: calculate_targeted_sell_price(purchase_price, traits.target_sell_percentage)
+            buy_fee_tier: 0,
+            borrow_fee: 0.0,
+            targeted_sell_price: calculate_targeted_sell_price(purchase_price, exit_rules.target_sell_percentage, side),
+            partial_fill: false,
+            leverage: 1.0,
+            borrowed: 0.0,
+            interest: 0.0
         }
     }
 
-    pub fn update_for_new_period(&mut self, current_price: f64, traits: &Traits) {
-        let trailing_stop_loss = calculate_stop_loss(current_price, traits.trailing_stop_loss);
+    // borrows the part of the position not covered by the money put up
+    pub fn apply_leverage(&mut self, leverage: f64) {
+        self.leverage = leverage;
+        self.borrowed = self.amount * self.purchase_price * (1.0 - (1.0 / leverage));
+        self.money_spent -= self.borrowed;
+    }
+
+    // splits off the given amount into its own holding so it can be sold on its own
+    // the money spent and fees are shared between the two holdings by amount
+    pub fn split(&mut self, amount: f64) -> CurrentHolding {
+        let fraction = amount / self.amount;
+
+        let mut split_holding = *self;
+        split_holding.amount = amount;
+        split_holding.money_spent = self.money_spent * fraction;
+        split_holding.buy_fee = self.buy_fee * fraction;
+        split_holding.borrow_fee = self.borrow_fee * fraction;
+        split_holding.borrowed = self.borrowed * fraction;
+        split_holding.interest = self.interest * fraction;
+
+        self.amount -= split_holding.amount;
+        self.money_spent -= split_holding.money_spent;
+        self.buy_fee -= split_holding.buy_fee;
+        self.borrow_fee -= split_holding.borrow_fee;
+        self.borrowed -= split_holding.borrowed;
+        self.interest -= split_holding.interest;
+
+        split_holding
+    }
+
+    pub fn update_for_new_period(&mut self, current_price: f64, exit_rules: &ExitRules) {
+        self.update_trailing_stop_loss(current_price, exit_rules);
+        self.periods_held += 1;
+    }
+
+    pub fn update_trailing_stop_loss(&mut self, current_price: f64, exit_rules: &ExitRules) {
+        let trailing_stop_loss = calculate_stop_loss(current_price, exit_rules.trailing_stop_loss, self.side);
+
+        // the trailing stop only ever moves in the direction of the trade
+        let moved = match self.side {
+            Side::Long => trailing_stop_loss > self.trailing_stop_loss,
+            Side::Short => trailing_stop_loss < self.trailing_stop_loss
+        };
 
-        if trailing_stop_loss > self.trailing_stop_loss {
+        if moved {
             self.trailing_stop_loss = trailing_stop_loss;
         }
+    }
 
-        self.periods_held += 1;
+    pub fn accrue_borrow_fee(&mut self, current_price: f64, short_borrow_fee_as_percentage: f64) {
+        if self.side == Side::Short {
+            self.borrow_fee += self.amount * current_price * short_borrow_fee_as_percentage;
+        }
+    }
+
+    pub fn accrue_interest(&mut self, borrow_interest_as_percentage: f64) {
+        self.interest += self.borrowed * borrow_interest_as_percentage;
+    }
+
+    // what the holding would be worth if closed at the given price before the sell fee
+    // a short returns its collateral plus the difference between the purchase price and the price it's bought back at
+    // a leveraged holding pays back what it borrowed along with the interest
+    pub fn value(&self, price: f64) -> f64 {
+        let value = match self.side {
+            Side::Long => self.amount * price,
+            Side::Short => (self.amount * ((2.0 * self.purchase_price) - price)) - self.borrow_fee
+        };
+
+        value - self.borrowed - self.interest
+    }
+
+    // the price at which the equity of the holding falls to the maintenance margin of its notional
+    pub fn liquidation_price(&self, maintenance_margin_as_percentage: f64) -> f64 {
+        let owed = self.borrowed + self.interest;
+
+        match self.side {
+            Side::Long => owed / (self.amount * (1.0 - maintenance_margin_as_percentage)),
+            Side::Short => ((2.0 * self.purchase_price * self.amount) - self.borrow_fee - owed) / (self.amount * (1.0 + maintenance_margin_as_percentage))
+        }
     }
 }
 
@@ -69,6 +170,7 @@ impl CurrentHolding {
 #[serde(rename_all = "camelCase")]
 pub struct SoldHolding {
     pub asset: Asset,
+    pub side: Side,
     pub amount: f64,
     pub purchase_time: u64,
     pub sell_time: u64,
@@ -84,16 +186,24 @@ pub struct SoldHolding {
     pub trailing_stop_loss: f64, // current trailing price to stop out at
     pub win: bool,
     pub buy_fee: f64,
+    pub buy_fee_tier: usize,
     pub sell_fee: f64,
-    pub targeted_sell_price: f64
+    pub sell_fee_tier: usize,
+    pub borrow_fee: f64,
+    pub leverage: f64,
+    pub interest: f64,
+    pub targeted_sell_price: f64,
+    pub intrabar_ordering: Option<IntrabarOrdering>, // set when the target and a stop were both hit in the period
+    pub partial_exit: bool // only part of the holding was sold because the period ran out of volume
 }
 
 impl SoldHolding {
-    pub fn new(holding_sold: &CurrentHolding, sell_price: f64, money_from_sell: f64, sell_fee: f64, sell_reason: SellReason, sell_time: u64) -> SoldHolding {
+    pub fn new(holding_sold: &CurrentHolding, sell_price: f64, money_from_sell: f64, sell_fee: f64, sell_reason: SellReason, sell_time: u64, intrabar_ordering: Option<IntrabarOrdering>) -> SoldHolding {
         SoldHolding {
             asset: holding_sold.asset,
+            side: holding_sold.side,
             purchase_time: holding_sold.purchase_time,
-            sell_time: sell_time,
+            sell_time,
             amount: holding_sold.amount,
             purchase_price: holding_sold.purchase_price,
             money_spent: holding_sold.money_spent,
@@ -107,8 +217,15 @@ impl SoldHolding {
             amount_gained: money_from_sell - holding_sold.money_spent,
             win: (money_from_sell - holding_sold.money_spent) > 0.0,
             buy_fee: holding_sold.buy_fee,
+            buy_fee_tier: holding_sold.buy_fee_tier,
             sell_fee,
-            targeted_sell_price: holding_sold.targeted_sell_price
+            sell_fee_tier: 0,
+            borrow_fee: holding_sold.borrow_fee,
+            leverage: holding_sold.leverage,
+            interest: holding_sold.interest,
+            targeted_sell_price: holding_sold.targeted_sell_price,
+            intrabar_ordering,
+            partial_exit: false
         }
     }
 }
\ No newline at end of file
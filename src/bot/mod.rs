@@ -1,168 +1,441 @@
 pub mod traits;
 pub mod holdings;
-use traits::Traits;
+pub mod orders;
+pub mod fitness;
+pub mod constraints;
 use holdings::*;
+use orders::*;
+use fitness::{RunSummary, Metrics};
+use constraints::{ConstraintViolation, ViolationAction};
 use std::sync::Arc;
+use std::collections::HashMap;
 use rand::Rng;
-use crate::config::Config;
+use crate::config::{Config, ExecutionModel, IntrabarOrdering};
 use crate::asset::Asset;
-use crate::price_data::PriceData;
+use crate::market::slippage::Fill;
+use crate::market::volume::UnfilledRemainder;
+use crate::market::fees::{Liquidity, TradedVolume};
+use crate::market::rules::TradingRules;
+use crate::price_data::{PriceData, MarketHistory};
+use crate::strategy::{Strategy, Momentum, PeriodContext, ExitRules};
 // use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Bot {
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct Bot<S: Strategy = Momentum> {
     pub id: u64,
-    pub traits: Traits,
-    pub money: f64,
+    pub island: u64, // the island the bot is bred on
+    pub migrated_from: Option<u64>, // set for the generation a bot arrives on from another island
+    pub traits: S::Genome,
+    pub money: f64, // held in the quote currency
+    pub money_in_usd: f64, // the money at the end of the run converted to USD
     pub value_history: Vec<ValueHistory>,
     pub current_holdings: Vec<CurrentHolding>,
     pub sold_holdings: Vec<SoldHolding>,
+    pub open_orders: Vec<Order>,
+    pub closed_orders: Vec<ClosedOrder>, // orders that expired or were cancelled before they filled
+    pub rejected_orders: u64, // orders and sells refused for breaking the trading rules
     pub fitness: f64,
+    pub shared_fitness: Option<f64>, // the fitness divided by the size of the bot's species when speciation is enabled
+    pub species: u64,
+    pub metrics: Metrics,
+    pub pareto_rank: u64, // the non-dominated front the bot is in, 0 is the pareto front
+    pub crowding_distance: f64, // how spread out the bot is from the rest of its front
+    pub violations: Vec<ConstraintViolation>, // the activity constraints the bot broke
+    pub disqualified: bool,
+    pub periods_traded: u64,
+    pub periods_in_market: u64, // periods that ended with a position held
     pub start_time: Option<u64>,
-    pub end_time: Option<u64>
+    pub end_time: Option<u64>,
+    #[serde(skip)]
+    pub traded_volume: TradedVolume
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ValueHistory {
-    pub value: f64,
+    pub value: f64, // in the quote currency
+    pub cash: f64, // money plus the money held by open orders
+    pub position_value: f64,
+    pub usd_value: f64,
+    pub balances: HashMap<Asset, f64>,
     pub time: u64
 }
 
-fn calculate_momentum(current_price: f64, previous_price: f64) -> f64 {
-    ((current_price - previous_price) / previous_price) * 100.0
-}
-
-fn get_sell_reason(traits: &Traits, holding: &CurrentHolding, current_price_data: &PriceData) -> SellReason {
-    // targeted sell price must always execute before anything else baby
-    if holding.targeted_sell_price < current_price_data.high {
-        return SellReason::TargetedSellPrice;
+// The intrabar ordering is returned when the target and a stop were both hit and the ordering policy had to pick one
+fn get_sell_reason(exit_rules: &ExitRules, holding: &CurrentHolding, current_price_data: &PriceData, execution_model: ExecutionModel, intrabar_ordering: IntrabarOrdering, maintenance_margin_as_percentage: f64) -> (SellReason, Option<IntrabarOrdering>) {
+    // with the close model stops are only checked against the close of the period
+    // with the intra candle model stops trigger as soon as the candle trades through them
+    let (stop_check_low, stop_check_high) = match execution_model {
+        ExecutionModel::Close => (current_price_data.close, current_price_data.close),
+        ExecutionModel::IntraCandle => (current_price_data.low, current_price_data.high)
+    };
+
+    // liquidation comes before everything else, the exchange doesn't wait for the minimum holding periods
+    let liquidation_price = holding.liquidation_price(maintenance_margin_as_percentage);
+    let liquidated = match holding.side {
+        Side::Long => stop_check_low <= liquidation_price,
+        Side::Short => stop_check_high >= liquidation_price
+    };
+
+    if liquidated {
+        return (SellReason::Liquidated, None);
     }
 
-    if holding.periods_held >= traits.maximum_holding_periods {
-        return SellReason::MaxPeriodsHeld;
+    // shorts are the mirror image of longs, they win when the price falls and stop out when it rises
+    let (target_hit, stop_loss_hit, trailing_stop_loss_hit) = match holding.side {
+        Side::Long => (
+            holding.targeted_sell_price < current_price_data.high,
+            stop_check_low <= holding.stop_loss,
+            stop_check_low <= holding.trailing_stop_loss
+        ),
+        Side::Short => (
+            holding.targeted_sell_price > current_price_data.low,
+            stop_check_high >= holding.stop_loss,
+            stop_check_high >= holding.trailing_stop_loss
+        )
+    };
+
+    let stop_reason = if holding.periods_held < exit_rules.minimum_holding_periods {
+        SellReason::None
+    } else if stop_loss_hit {
+        SellReason::StopLoss
+    } else if trailing_stop_loss_hit {
+        SellReason::TrailingStopLoss
+    } else {
+        SellReason::None
+    };
+
+    if target_hit {
+        if stop_reason == SellReason::None {
+            return (SellReason::TargetedSellPrice, None);
+        }
+
+        let stop_first = match intrabar_ordering {
+            IntrabarOrdering::Optimistic => false,
+            IntrabarOrdering::Pessimistic => true,
+            IntrabarOrdering::OpenToClose => {
+                // a candle closing at or above its open is assumed to have gone open -> low -> high -> close
+                let low_first = current_price_data.close >= current_price_data.open;
+
+                match holding.side {
+                    Side::Long => low_first,
+                    Side::Short => !low_first
+                }
+            }
+        };
+
+        return match stop_first {
+            true => (stop_reason, Some(intrabar_ordering)),
+            false => (SellReason::TargetedSellPrice, Some(intrabar_ordering))
+        };
     }
 
-    if holding.periods_held < traits.minimum_holding_periods {
-        return SellReason::None;
+    if holding.periods_held >= exit_rules.maximum_holding_periods {
+        return (SellReason::MaxPeriodsHeld, None);
     }
 
-    if current_price_data.close <= holding.stop_loss {
-        return SellReason::StopLoss
+    (stop_reason, None)
+}
+
+// the exit levels of a holding as the exchange would have them, on the price ticks of the asset
+fn round_exit_levels(holding: &CurrentHolding, trading_rules: &TradingRules) -> CurrentHolding {
+    let mut holding = *holding;
+    holding.targeted_sell_price = trading_rules.round_target_price(holding.targeted_sell_price, holding.side);
+    holding.stop_loss = trading_rules.round_stop_price(holding.stop_loss, holding.side);
+    holding.trailing_stop_loss = trading_rules.round_stop_price(holding.trailing_stop_loss, holding.side);
+
+    holding
+}
+
+// A stop that is hit inside the candle fills at the stop price
+// unless the candle opened past the stop, in which case we only get the open
+fn calculate_stop_fill_price(stop_price: f64, side: Side, current_price_data: &PriceData) -> f64 {
+    match side {
+        Side::Long => stop_price.min(current_price_data.open),
+        Side::Short => stop_price.max(current_price_data.open)
     }
+}
 
-    if current_price_data.close <= holding.trailing_stop_loss {
-        return SellReason::TrailingStopLoss
+fn get_sell_price(sell_reason: SellReason, holding: &CurrentHolding, current_price_data: &PriceData, execution_model: ExecutionModel, maintenance_margin_as_percentage: f64) -> f64 {
+    match (sell_reason, execution_model) {
+        (SellReason::Liquidated, ExecutionModel::IntraCandle) => calculate_stop_fill_price(holding.liquidation_price(maintenance_margin_as_percentage), holding.side, current_price_data),
+        (SellReason::TargetedSellPrice, _) => holding.targeted_sell_price,
+        (SellReason::StopLoss, ExecutionModel::IntraCandle) => calculate_stop_fill_price(holding.stop_loss, holding.side, current_price_data),
+        (SellReason::TrailingStopLoss, ExecutionModel::IntraCandle) => calculate_stop_fill_price(holding.trailing_stop_loss, holding.side, current_price_data),
+        _ => current_price_data.close
     }
+}
+
+// charges the fee for a trade and adds the trade to the bot's traded volume
+// returns the fee and the fee schedule tier it was charged at
+fn calculate_fee<S: Strategy>(config: &Config<S>, traded_volume: &mut TradedVolume, notional: f64, liquidity: Liquidity, time: u64) -> (f64, usize) {
+    let fee = match &config.fee_schedule {
+        Some(fee_schedule) => fee_schedule.fee(notional, liquidity, traded_volume.rolling_volume(time, fee_schedule.window_in_seconds())),
+        None => (notional * config.transaction_fee_as_percentage, 0)
+    };
 
-    SellReason::None
+    traded_volume.record(time, notional);
+
+    fee
 }
 
-fn calculate_sell_fee(price: f64, transaction_fee_as_percentage: f64, amount: f64) -> f64 {
-    price * amount * transaction_fee_as_percentage
+fn calculate_money_from_sell(holding: &CurrentHolding, price: f64, sell_fee: f64) -> f64 {
+    holding.value(price) - sell_fee
 }
 
-fn calculate_money_from_sell(price: f64, sell_fee: f64, amount: f64) -> f64 {
-    (price * amount) - sell_fee
+// The price holdings are valued at in the value history
+enum MarkPrice {
+    Open,
+    Close
 }
 
-fn calculate_amount_to_buy(money_to_spend: f64, current_price: f64) -> f64 {
-    // we round to four decimal places
-    let purchase_amount = money_to_spend /  current_price;
-    return (purchase_amount * 10000.0).round() / 10000.0
+enum FillOutcome {
+    Filled,
+    Unfilled, // nothing or only part of the order filled
+    Rejected
 }
 
-impl Bot {
-    pub fn new(config: &Config, id: u64) -> Bot {
+impl<S: Strategy> Bot<S> {
+    pub fn new(config: &Config<S>, id: u64) -> Bot<S> {
+        let mut rng = rand::thread_rng();
+
+        Bot::from_traits(S::random_genome(&mut rng, config), config, id)
+    }
+
+    pub(crate) fn from_traits(traits: S::Genome, config: &Config<S>, id: u64) -> Bot<S> {
         Bot {
             id,
-            traits: Traits::new(config),
+            island: 0,
+            migrated_from: None,
+            traits,
             money: config.starting_money,
+            money_in_usd: 0.0,
             value_history: Vec::<ValueHistory>::new(),
             current_holdings: Vec::<CurrentHolding>::new(),
             sold_holdings: Vec::<SoldHolding>::new(),
+            open_orders: Vec::<Order>::new(),
+            closed_orders: Vec::<ClosedOrder>::new(),
+            rejected_orders: 0,
             fitness: 0.0,
+            shared_fitness: None,
+            species: 0,
+            metrics: Metrics::default(),
+            pareto_rank: 0,
+            crowding_distance: 0.0,
+            violations: Vec::<ConstraintViolation>::new(),
+            disqualified: false,
+            periods_traded: 0,
+            periods_in_market: 0,
             start_time: None,
-            end_time: None
+            end_time: None,
+            traded_volume: TradedVolume::default()
         }
     }
 
-    pub fn create_clone(&self, config: &Config, id: u64) -> Bot {
-        let traits_copy = self.traits.clone();
-        let money = config.starting_money;
-
-        Bot {
-            id,
-            traits: traits_copy,
-            money,
-            value_history: Vec::<ValueHistory>::new(),
-            current_holdings: Vec::<CurrentHolding>::new(),
-            sold_holdings: Vec::<SoldHolding>::new(),
-            fitness: 0.0,
-            start_time: None,
-            end_time: None
-        }
+    pub fn create_clone(&self, config: &Config<S>, id: u64) -> Bot<S> {
+        Bot::from_traits(self.traits, config, id)
     }
 
-    fn handle_buy(&mut self, config: &Config, current_price_data: &PriceData, old_price_data: &PriceData) {
-        let money_to_spend = self.money * (self.traits.percent_purchase / 100.0);
+    fn handle_buy(&mut self, config: &Config<S>, market_history: &MarketHistory, period: u64) {
+        let context = PeriodContext {
+            period,
+            market_history
+        };
+
+        let entry_signal = match S::decide(&self.traits, &context) {
+            Some(entry_signal) => entry_signal,
+            None => return
+        };
+
+        let current_price_data = match market_history.price_data(entry_signal.asset, period) {
+            Some(current_price_data) => current_price_data,
+            None => return
+        };
+
+        let money_to_spend = self.money * (entry_signal.percent_purchase / 100.0);
         if money_to_spend < config.minimum_purchase_size {
             return;
         }
 
-        let current_price = current_price_data.open;
-        let momentum = calculate_momentum(current_price, old_price_data.open);
-
-        if momentum < self.traits.minimum_buy_momentum || momentum > self.traits.maximum_buy_momentum {
+        let trading_rules = config.trading_rules_for(entry_signal.asset);
+        if !trading_rules.meets_minimum_notional(money_to_spend * entry_signal.leverage) {
+            self.rejected_orders += 1;
             return;
         }
 
-        let amount_to_buy = calculate_amount_to_buy(money_to_spend, current_price);
-        let money_spent_no_fee = amount_to_buy * current_price;
+        // the money is held by the order until it fills, expires or is cancelled
+        let mut order = Order::new(entry_signal.asset, entry_signal.side, entry_signal.order_type, current_price_data, entry_signal.entry_offset_percentage, money_to_spend, entry_signal.order_expiry_periods);
+        order.leverage = entry_signal.leverage;
+        order.price = trading_rules.round_price(order.price);
+
+        self.money -= order.money_reserved;
+        self.open_orders.push(order);
+    }
+
+    fn fill_order(&mut self, config: &Config<S>, order: &mut Order, fill_price: f64, current_price_data: &PriceData, volume_available: &mut f64) -> FillOutcome {
+        // limit orders rest on the book so they fill at their price, everything else takes liquidity and slips
+        let (fill_price, liquidity) = match order.order_type {
+            OrderType::Limit => (fill_price, Liquidity::Maker),
+            _ => (config.slippage.apply(fill_price, order.side, Fill::Entry, current_price_data), Liquidity::Taker)
+        };
+
+        let trading_rules = config.trading_rules_for(order.asset);
+        let amount_wanted = trading_rules.round_amount((order.money_reserved * order.leverage) / fill_price);
+        if !trading_rules.meets_minimum_notional(amount_wanted * fill_price) {
+            return FillOutcome::Rejected;
+        }
+
+        // a fill cut short by the volume waits for more volume instead of breaking the minimum notional
+        let amount_to_buy = trading_rules.amount_that_fits(amount_wanted, *volume_available);
+        if amount_to_buy <= 0.0 || !trading_rules.meets_minimum_notional(amount_to_buy * fill_price) {
+            return FillOutcome::Unfilled;
+        }
 
-        let fee = money_spent_no_fee * config.transaction_fee_as_percentage;
-        let money_spent = money_spent_no_fee + fee;
+        *volume_available -= amount_to_buy;
 
-        let new_holding = CurrentHolding::new(current_price, current_price_data.time, amount_to_buy, money_spent, Asset::ETH, &self.traits, fee);
+        let money_spent_no_fee = amount_to_buy * fill_price;
+        let (fee, fee_tier) = calculate_fee(config, &mut self.traded_volume, money_spent_no_fee, liquidity, current_price_data.time);
+
+        let mut new_holding = CurrentHolding::new(fill_price, current_price_data.time, amount_to_buy, order.asset, order.side, &S::exit_rules(&self.traits), fee);
+        new_holding.buy_fee_tier = fee_tier;
+        new_holding.apply_leverage(order.leverage);
+        order.amount_filled += amount_to_buy;
+
+        // whatever is left over is given back once there isn't enough left for another purchase
+        let money_left = order.money_reserved - new_holding.money_spent;
+        let completely_filled = amount_to_buy >= amount_wanted || money_left < config.minimum_purchase_size;
+        new_holding.partial_fill = !completely_filled;
 
         self.current_holdings.push(new_holding);
-        self.money -= money_spent;
+
+        match completely_filled {
+            true => {
+                self.money += money_left;
+                order.money_reserved = 0.0;
+                FillOutcome::Filled
+            },
+            false => {
+                order.money_reserved = money_left;
+                FillOutcome::Unfilled
+            }
+        }
     }
 
-    fn handle_sell(&mut self, config: &Config, current_price_data: &PriceData) {
-        for holding in &mut self.current_holdings {
-            holding.update_for_new_period(current_price_data.close, &self.traits)
+    fn close_order(&mut self, order: Order, close_reason: OrderCloseReason, close_time: u64) {
+        self.money += order.money_reserved;
+        self.closed_orders.push(ClosedOrder::new(order, close_reason, close_time));
+    }
+
+    fn handle_orders(&mut self, config: &Config<S>, market_history: &MarketHistory, period: u64, volume_available: &mut HashMap<Asset, f64>) {
+        let open_orders: Vec::<Order> = self.open_orders.drain(..).collect();
+
+        for mut order in open_orders {
+            let current_price_data = market_history.price_data(order.asset, period).unwrap();
+            let volume_available = volume_available.get_mut(&order.asset).unwrap();
+
+            if let Some(fill_price) = order.get_fill_price(current_price_data) {
+                match self.fill_order(config, &mut order, fill_price, current_price_data, volume_available) {
+                    FillOutcome::Filled => continue,
+                    FillOutcome::Rejected => {
+                        self.rejected_orders += 1;
+                        self.close_order(order, OrderCloseReason::Rejected, current_price_data.time);
+                        continue;
+                    },
+                    FillOutcome::Unfilled => {
+                        if config.volume_constraint.unfilled_remainder == UnfilledRemainder::Cancel {
+                            self.close_order(order, OrderCloseReason::Cancelled, current_price_data.time);
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            order.periods_open += 1;
+
+            match order.is_expired() {
+                true => self.close_order(order, OrderCloseReason::Expired, current_price_data.time),
+                false => self.open_orders.push(order)
+            }
         }
+    }
 
-        let mut sold_holdings: Vec::<SoldHolding> = Vec::<SoldHolding>::new();
+    fn handle_sell(&mut self, config: &Config<S>, market_history: &MarketHistory, period: u64, volume_available: &mut HashMap<Asset, f64>) {
+        let exit_rules = S::exit_rules(&self.traits);
+        let execution_model = config.execution_model;
 
         for holding in &mut self.current_holdings {
-            // lambda that sells the holding
-            let sell_holding = |reason: SellReason| -> SoldHolding {
-                if reason == SellReason::TargetedSellPrice {
-                    let sell_fee = calculate_sell_fee(holding.targeted_sell_price, config.transaction_fee_as_percentage, holding.amount);
-                    let money_from_sell = calculate_money_from_sell(holding.targeted_sell_price, sell_fee, holding.amount);
+            let current_price_data = market_history.price_data(holding.asset, period).unwrap();
+            holding.accrue_borrow_fee(current_price_data.close, config.short_borrow_fee_as_percentage);
+            holding.accrue_interest(config.margin.borrow_interest_as_percentage);
+
+            // intra candle stops have to be checked against the levels that existed during the candle
+            // so the trailing stop is only moved up to the close once we know the holding survived
+            match execution_model {
+                ExecutionModel::Close => holding.update_for_new_period(current_price_data.close, &exit_rules),
+                ExecutionModel::IntraCandle => holding.periods_held += 1
+            }
+        }
 
-                    return SoldHolding::new(&holding, holding.targeted_sell_price, money_from_sell, sell_fee, reason, current_price_data.time);
-                }
+        let sell_reasons: Vec::<(SellReason, Option<IntrabarOrdering>)> = self.current_holdings
+            .iter()
+            .map(|holding| get_sell_reason(&exit_rules, &round_exit_levels(holding, &config.trading_rules_for(holding.asset)), market_history.price_data(holding.asset, period).unwrap(), execution_model, config.intrabar_ordering, config.margin.maintenance_margin_as_percentage))
+            .collect();
 
-                let sell_fee = calculate_sell_fee(current_price_data.close, config.transaction_fee_as_percentage, holding.amount);
-                let money_from_sell = calculate_money_from_sell(current_price_data.close, sell_fee, holding.amount);
+        // the equity curve is recorded at the end of the period instead
+        if !config.equity_curve.enabled && sell_reasons.iter().any(|(sell_reason, _)| *sell_reason != SellReason::None) {
+            self.update_value_history(market_history, period, MarkPrice::Open);
+        }
 
-                return SoldHolding::new(&holding, current_price_data.close, money_from_sell, sell_fee, reason, current_price_data.time);
-            };
+        let mut sold_holdings: Vec::<SoldHolding> = Vec::<SoldHolding>::new();
+        let mut keep_holdings = Vec::<bool>::new();
+
+        for (holding, (sell_reason, intrabar_ordering)) in self.current_holdings.iter_mut().zip(sell_reasons.iter()) {
+            let current_price_data = market_history.price_data(holding.asset, period).unwrap();
+            let volume_available = volume_available.get_mut(&holding.asset).unwrap();
 
-            let sell_reason = get_sell_reason(&self.traits, &holding, &current_price_data);
+            let trading_rules = config.trading_rules_for(holding.asset);
 
-            if sell_reason != SellReason::None {
-                sold_holdings.push(sell_holding(sell_reason));
+            // when the period runs out of volume the rest of the holding stays open for the next period
+            let amount_to_sell = trading_rules.amount_that_fits(holding.amount, *volume_available);
+            if *sell_reason == SellReason::None || amount_to_sell <= 0.0 {
+                keep_holdings.push(true);
+                continue;
+            }
+
+            let exit_levels = round_exit_levels(holding, &trading_rules);
+            let sell_price = trading_rules.round_price(get_sell_price(*sell_reason, &exit_levels, current_price_data, execution_model, config.margin.maintenance_margin_as_percentage));
+
+            // a whole holding can always be closed but selling part of one has to meet the minimum notional
+            let partial_exit = amount_to_sell < holding.amount;
+            if partial_exit && !trading_rules.meets_minimum_notional(amount_to_sell * sell_price) {
+                self.rejected_orders += 1;
+                keep_holdings.push(true);
+                continue;
             }
-        }
 
+            *volume_available -= amount_to_sell;
 
-        if sold_holdings.len() > 0 {
-            self.update_value_history(current_price_data);
+            let holding_sold = match partial_exit {
+                true => holding.split(amount_to_sell),
+                false => *holding
+            };
+            keep_holdings.push(partial_exit);
+
+            let holding = &holding_sold;
+
+            // the targeted sell price is a resting order, every other exit takes liquidity and slips
+            let (sell_price, liquidity) = match sell_reason {
+                SellReason::TargetedSellPrice => (sell_price, Liquidity::Maker),
+                _ => (config.slippage.apply(sell_price, holding.side, Fill::Exit, current_price_data), Liquidity::Taker)
+            };
+            let (sell_fee, sell_fee_tier) = calculate_fee(config, &mut self.traded_volume, sell_price * holding.amount, liquidity, current_price_data.time);
+            let money_from_sell = calculate_money_from_sell(holding, sell_price, sell_fee);
+
+            let mut sold_holding = SoldHolding::new(holding, sell_price, money_from_sell, sell_fee, *sell_reason, current_price_data.time, *intrabar_ordering);
+            sold_holding.sell_fee_tier = sell_fee_tier;
+            sold_holding.partial_exit = partial_exit;
+            sold_holdings.push(sold_holding);
         }
 
         // update the amount of money
@@ -171,183 +444,230 @@ impl Bot {
             .for_each(|holding| self.money += holding.money_from_sell);
         self.sold_holdings.append(&mut sold_holdings);
 
-        self.current_holdings = self.current_holdings.to_owned()
-            .into_iter()
-            .filter(|holding| {
-                get_sell_reason(&self.traits, &holding, &current_price_data) == SellReason::None
-            })
-            .collect();
+        let mut keep_holdings = keep_holdings.iter();
+        self.current_holdings
+            .retain(|_| *keep_holdings.next().unwrap());
+
+        if execution_model == ExecutionModel::IntraCandle {
+            for holding in &mut self.current_holdings {
+                let current_price_data = market_history.price_data(holding.asset, period).unwrap();
+                holding.update_trailing_stop_loss(current_price_data.close, &exit_rules);
+            }
+        }
     }
 
-    fn sell_all(&mut self, current_price_data: &PriceData, transaction_fee_as_percentage: f64) {
-        for holding in &mut self.current_holdings {
-            holding.update_for_new_period(current_price_data.close, &self.traits);
+    // the end of the run is not held to the volume constraint or trading rules, everything has to be sold
+    fn sell_all(&mut self, config: &Config<S>, market_history: &MarketHistory, period: u64) {
+        let exit_rules = S::exit_rules(&self.traits);
 
-            let sell_fee = calculate_sell_fee(current_price_data.close, transaction_fee_as_percentage, holding.amount);
-            let money_from_sell = calculate_money_from_sell(current_price_data.close, sell_fee, holding.amount);
+        let open_orders: Vec::<Order> = self.open_orders.drain(..).collect();
+        for order in open_orders {
+            let close_time = market_history.price_data(order.asset, period).unwrap().time;
+            self.close_order(order, OrderCloseReason::Cancelled, close_time);
+        }
 
-            self.sold_holdings.push(SoldHolding::new(&holding, current_price_data.close, money_from_sell, sell_fee, SellReason::Forced, current_price_data.time));
+        for holding in &mut self.current_holdings {
+            let current_price_data = market_history.price_data(holding.asset, period).unwrap();
+            holding.accrue_borrow_fee(current_price_data.close, config.short_borrow_fee_as_percentage);
+            holding.accrue_interest(config.margin.borrow_interest_as_percentage);
+            holding.update_for_new_period(current_price_data.close, &exit_rules);
+
+            let close_price = config.trading_rules_for(holding.asset).round_price(current_price_data.close);
+            let sell_price = config.slippage.apply(close_price, holding.side, Fill::Exit, current_price_data);
+            let (sell_fee, sell_fee_tier) = calculate_fee(config, &mut self.traded_volume, sell_price * holding.amount, Liquidity::Taker, current_price_data.time);
+            let money_from_sell = calculate_money_from_sell(holding, sell_price, sell_fee);
+
+            let mut sold_holding = SoldHolding::new(holding, sell_price, money_from_sell, sell_fee, SellReason::Forced, current_price_data.time, None);
+            sold_holding.sell_fee_tier = sell_fee_tier;
+            self.sold_holdings.push(sold_holding);
             self.money += money_from_sell;
         }
         self.current_holdings.clear();
+        self.money_in_usd = self.money * market_history.quote_in_usd_at_close(period);
 
         // in sell all we update the fitness of the bot since it's meant to be the final run
-        self.calculate_fitness();
+        self.calculate_fitness(config, market_history);
     }
 
-    fn update_value_history(&mut self, current_price_data: &PriceData) {
-        let price = current_price_data.open;
-        let holding_value: f64 = self.current_holdings
+    // every holding is marked to its own asset
+    fn update_value_history(&mut self, market_history: &MarketHistory, period: u64, mark_price: MarkPrice) {
+        let position_value: f64 = self.current_holdings
             .iter()
-            .map(|x| x.amount * price)
+            .map(|holding| {
+                let price_data = market_history.price_data(holding.asset, period).unwrap();
+
+                match mark_price {
+                    MarkPrice::Open => holding.value(price_data.open),
+                    MarkPrice::Close => holding.value(price_data.close)
+                }
+            })
+            .sum();
+        let reserved_money: f64 = self.open_orders
+            .iter()
+            .map(|order| order.money_reserved)
             .sum();
-        let total_value = self.money + holding_value;
+        let cash = self.money + reserved_money;
+        let total_value = cash + position_value;
+
+        let quote_in_usd = match mark_price {
+            MarkPrice::Open => market_history.quote_in_usd_at_open(period),
+            MarkPrice::Close => market_history.quote_in_usd_at_close(period)
+        };
 
         self.value_history.push(ValueHistory {
             value: total_value,
-            time: current_price_data.time
+            cash,
+            position_value,
+            usd_value: total_value * quote_in_usd,
+            balances: self.balances(market_history.quote_asset()),
+            time: market_history.time(period).unwrap()
         })
     }
 
+    // how much of each asset the bot has, shorts are negative since the asset is owed
+    // money held by open orders is still counted towards the quote currency
+    pub fn balances(&self, quote_asset: Asset) -> HashMap<Asset, f64> {
+        let mut balances = HashMap::<Asset, f64>::new();
+
+        let reserved_money: f64 = self.open_orders
+            .iter()
+            .map(|order| order.money_reserved)
+            .sum();
+        balances.insert(quote_asset, self.money + reserved_money);
+
+        for holding in &self.current_holdings {
+            let amount = match holding.side {
+                Side::Long => holding.amount,
+                Side::Short => -holding.amount
+            };
+
+            *balances.entry(holding.asset).or_insert(0.0) += amount;
+        }
+
+        balances
+    }
+
     // TODO: For now we will check every period
     // We will buy on open and sell on close
     // In the future we should set how often to buy and sell
     // In addition, we should set if to buy on open or close
     // Sell would occur on the flip? Or maybe be configurable by trait
-    pub fn run_period(&mut self, price_history: &Arc<Vec<PriceData>>, period: u64, config: &Arc<Config>) {
+    pub fn run_period(&mut self, market_history: &Arc<MarketHistory>, period: u64, config: &Arc<Config<S>>) {
         if self.start_time.is_none() {
-            self.start_time = Some(price_history.get(0).unwrap().time);
+            self.start_time = market_history.time(0);
         }
 
-        if period < self.traits.number_of_averaging_periods {
-            return;
-        }
+        self.trade_period(market_history, period, config);
 
-        let old_price_data = price_history.get((period - self.traits.number_of_averaging_periods) as usize).unwrap();
-        let current_price_data = price_history.get((period) as usize).unwrap();
-
-        // end of run
-        if price_history.len() == (period + 1)  as usize {
-            self.sell_all(&current_price_data, config.transaction_fee_as_percentage);
-            self.end_time = Some(current_price_data.time);
-            return;
+        if period >= S::warmup_periods(&self.traits) {
+            self.periods_traded += 1;
+            if !self.current_holdings.is_empty() {
+                self.periods_in_market += 1;
+            }
         }
 
-        self.handle_buy(&config, &current_price_data, &old_price_data);
-        self.handle_sell(&config, &current_price_data);
+        let last_period = market_history.len() == (period + 1) as usize;
+        if config.equity_curve.enabled && (last_period || period.is_multiple_of(config.equity_curve.sample_every_periods)) {
+            self.update_value_history(market_history, period, MarkPrice::Close);
+        }
     }
 
-    // for now the calculation for fitness will be simple
-    // we can work on a more complicated version once we have graphs in place
-    fn calculate_fitness(&mut self) {
-        if self.sold_holdings.len() == 0 {
-            self.fitness = 0.0;
+    fn trade_period(&mut self, market_history: &MarketHistory, period: u64, config: &Config<S>) {
+        if period < S::warmup_periods(&self.traits) {
             return;
         }
 
-        self.fitness = self.money;
-    }
-
-    // Hamming is the average percent difference between all traits
-    // This is used for determining if the bots are to similar to breeed
-    pub fn hamming(&self, bot_two: &Bot) -> f64 {
-        let calculate_percent_difference = |value_one: f64, value_two: f64| -> f64 {
-            let diff = (value_one - value_two) / ((value_one + value_two) / 2.0);
+        // end of run
+        if market_history.len() == (period + 1)  as usize {
+            self.end_time = market_history.time(period);
+            self.sell_all(config, market_history, period);
+            return;
+        }
 
-            return diff.abs() * 100.0;
-        };
+        let mut volume_available: HashMap<Asset, f64> = market_history
+            .assets()
+            .map(|asset| (*asset, config.volume_constraint.available_volume(market_history.price_data(*asset, period).unwrap())))
+            .collect();
 
-        let traits_one = self.traits;
-        let traits_two = bot_two.traits;
-
-        let number_of_averaging_periods_percent_diff = calculate_percent_difference(traits_one.number_of_averaging_periods as f64, traits_two.number_of_averaging_periods as f64);
-        let minimum_buy_momentum_percent_diff = calculate_percent_difference(traits_one.minimum_buy_momentum as f64, traits_two.minimum_buy_momentum as f64);
-        let maximum_buy_momentum_percent_diff = calculate_percent_difference(traits_one.maximum_buy_momentum as f64, traits_two.maximum_buy_momentum as f64);
-        let trailing_stop_loss_percent_diff = calculate_percent_difference(traits_one.trailing_stop_loss as f64, traits_two.trailing_stop_loss as f64);
-        let stop_loss_percent_diff = calculate_percent_difference(traits_one.stop_loss as f64, traits_two.stop_loss as f64);
-        let minimum_holding_periods_percent_diff = calculate_percent_difference(traits_one.minimum_holding_periods as f64, traits_two.minimum_holding_periods as f64);
-        let maximum_holding_periods_percent_diff = calculate_percent_difference(traits_one.maximum_holding_periods as f64, traits_two.maximum_holding_periods as f64);
-        let percent_purchase_percent_diff = calculate_percent_difference(traits_one.percent_purchase as f64, traits_two.percent_purchase as f64);
-        let target_sell_percentage_percent_diff = calculate_percent_difference(traits_one.target_sell_percentage as f64, traits_two.target_sell_percentage as f64);
-
-        return (number_of_averaging_periods_percent_diff + minimum_buy_momentum_percent_diff + maximum_buy_momentum_percent_diff + trailing_stop_loss_percent_diff
-        + stop_loss_percent_diff + minimum_holding_periods_percent_diff + maximum_holding_periods_percent_diff
-        + percent_purchase_percent_diff + target_sell_percentage_percent_diff) / 9.0
+        self.handle_buy(config, market_history, period);
+        self.handle_orders(config, market_history, period, &mut volume_available);
+        self.handle_sell(config, market_history, period, &mut volume_available);
     }
 
-    pub fn breed<R: Rng>(&self, bot_two: &Bot, rng: &mut R, config: &Config, id: u64) -> Bot {
-        let traits_one = self.traits;
-        let traits_two = bot_two.traits;
-
-        let number_of_averaging_periods = match rng.gen_bool(0.5) {
-            true => traits_one.number_of_averaging_periods,
-            false => traits_two.number_of_averaging_periods
+    // bots that never traded are given nothing no matter what the fitness function is
+    // bots that broke the activity constraints are then disqualified or have their penalty taken off
+    // the equity runs from the starting money to the final money with the value history in between
+    // the baseline is lined up with the equity so the two can be compared
+    fn calculate_fitness(&mut self, config: &Config<S>, market_history: &MarketHistory) {
+        let mut equity = vec!(config.starting_money);
+        equity.extend(self.value_history.iter().map(|value_history| value_history.value));
+        equity.push(self.money);
+
+        // the baseline is taken at the same times as the value history, the excess return only needs its final value
+        let times: Vec<u64> = self.value_history.iter().map(|value_history| value_history.time).collect();
+        let benchmark = match (market_history.baseline(), self.end_time) {
+            (Some(baseline), Some(end_time)) => baseline.equity(&times, end_time),
+            _ => None
         };
-
-        let minimum_buy_momentum = match rng.gen_bool(0.5) {
-            true => traits_one.minimum_buy_momentum,
-            false => traits_two.minimum_buy_momentum
+        let benchmark_return = match (market_history.baseline(), self.end_time) {
+            (Some(baseline), Some(end_time)) => baseline.value_at(end_time).map(|value| (value / baseline.starting_money) - 1.0),
+            _ => None
         };
 
-        let maximum_buy_momentum = match rng.gen_bool(0.5) {
-            true => traits_one.maximum_buy_momentum,
-            false => traits_two.maximum_buy_momentum
+        let summary = RunSummary {
+            equity: &equity,
+            sold_holdings: &self.sold_holdings,
+            years: fitness::years_between(self.start_time, self.end_time),
+            benchmark: benchmark.as_deref(),
+            benchmark_return,
+            exposure: match self.periods_traded {
+                0 => 0.0,
+                periods_traded => self.periods_in_market as f64 / periods_traded as f64
+            }
         };
 
-        let trailing_stop_loss = match rng.gen_bool(0.5) {
-            true => traits_one.trailing_stop_loss,
-            false => traits_two.trailing_stop_loss
-        };
+        self.metrics = Metrics::new(&summary);
 
-        let stop_loss = match rng.gen_bool(0.5) {
-            true => traits_one.stop_loss,
-            false => traits_two.stop_loss
-        };
+        let violations = config.activity_constraints.check(&self.metrics);
+        self.violations = violations.iter().map(|(violation, _)| *violation).collect();
 
-        let minimum_holding_periods = match rng.gen_bool(0.5) {
-            true => traits_one.minimum_holding_periods,
-            false => traits_two.minimum_holding_periods
-        };
+        if self.sold_holdings.is_empty() {
+            self.fitness = 0.0;
+        } else {
+            self.fitness = config.fitness_function.calculate(&summary);
+        }
 
-        let maximum_holding_periods = match rng.gen_bool(0.5) {
-            true => traits_one.maximum_holding_periods,
-            false => traits_two.maximum_holding_periods
-        };
+        if violations.is_empty() {
+            return;
+        }
 
-        let percent_purchase = match rng.gen_bool(0.5) {
-            true => traits_one.percent_purchase,
-            false => traits_two.percent_purchase
-        };
+        match config.activity_constraints.action {
+            ViolationAction::Disqualify => {
+                self.disqualified = true;
+                self.fitness = 0.0;
+            },
+            ViolationAction::Penalty => {
+                let breach: f64 = violations.iter().map(|(_, breach)| breach).sum();
+                self.fitness -= config.activity_constraints.penalty * breach;
+            }
+        }
+    }
 
-        let target_sell_percentage = match rng.gen_bool(0.5) {
-            true => traits_one.target_sell_percentage,
-            false => traits_two.target_sell_percentage
-        };
+    // Hamming is the difference between the genomes of two bots as defined by the strategy
+    // This is used for determining if the bots are to similar to breeed
+    pub fn hamming(&self, bot_two: &Bot<S>) -> f64 {
+        S::distance(&self.traits, &bot_two.traits)
+    }
 
-        let mut traits = Traits {
-            number_of_averaging_periods,
-            minimum_buy_momentum,
-            maximum_buy_momentum,
-            trailing_stop_loss,
-            stop_loss,
-            minimum_holding_periods,
-            maximum_holding_periods,
-            percent_purchase,
-            target_sell_percentage
-        };
+    // the fitness the bot is picked for breeding on
+    pub fn selection_fitness(&self) -> f64 {
+        self.shared_fitness.unwrap_or(self.fitness)
+    }
 
-        traits.mutate(rng, config);
+    pub fn breed<R: Rng>(&self, bot_two: &Bot<S>, rng: &mut R, config: &Config<S>, id: u64, generation: u64) -> Bot<S> {
+        let mut traits = S::crossover(&self.traits, &bot_two.traits, rng, config);
+        S::mutate(&mut traits, rng, config, generation);
 
-        return Bot {
-            id,
-            traits,
-            money: config.starting_money,
-            value_history: Vec::<ValueHistory>::new(),
-            current_holdings: Vec::<CurrentHolding>::new(),
-            sold_holdings: Vec::<SoldHolding>::new(),
-            fitness: 0.0,
-            start_time: None,
-            end_time: None
-        };
+        Bot::from_traits(traits, config, id)
     }
 }
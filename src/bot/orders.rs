@@ -0,0 +1,101 @@
+use crate::asset::Asset;
+use crate::bot::holdings::Side;
+use crate::price_data::PriceData;
+
+// Market orders fill straight away at the open of the period
+// Limit orders wait for the price to come back to them and stop entry orders wait for the price to break out to them
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub enum OrderType {
+    #[default]
+    Market,
+    Limit,
+    StopEntry
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum OrderCloseReason {
+    Expired,
+    Cancelled,
+    Rejected // the order broke the trading rules of its asset
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub asset: Asset,
+    pub side: Side,
+    pub order_type: OrderType,
+    pub price: f64, // the limit or stop price, for market orders the open of the period it was submitted on
+    pub money_reserved: f64, // what is left of the money set aside for the order
+    pub amount_filled: f64,
+    pub submitted_time: u64,
+    pub periods_open: u64,
+    pub expiry_periods: u64,
+    pub leverage: f64 // the position is this many times the money reserved
+}
+
+fn calculate_order_price(open: f64, side: Side, order_type: OrderType, entry_offset_percentage: f64) -> f64 {
+    let offset = open * (entry_offset_percentage / 100.0);
+
+    // limit orders try to get a better price then the open, stop entries wait for the move to continue
+    match (order_type, side) {
+        (OrderType::Market, _) => open,
+        (OrderType::Limit, Side::Long) | (OrderType::StopEntry, Side::Short) => open - offset,
+        (OrderType::Limit, Side::Short) | (OrderType::StopEntry, Side::Long) => open + offset
+    }
+}
+
+impl Order {
+    pub fn new(asset: Asset, side: Side, order_type: OrderType, current_price_data: &PriceData, entry_offset_percentage: f64, money_reserved: f64, expiry_periods: u64) -> Order {
+        Order {
+            asset,
+            side,
+            order_type,
+            price: calculate_order_price(current_price_data.open, side, order_type, entry_offset_percentage),
+            money_reserved,
+            amount_filled: 0.0,
+            submitted_time: current_price_data.time,
+            periods_open: 0,
+            expiry_periods,
+            leverage: 1.0
+        }
+    }
+
+    // the price the order fills at during the period or None if the period never reached the order
+    // if the period opens past the order price we fill at the open
+    pub fn get_fill_price(&self, current_price_data: &PriceData) -> Option<f64> {
+        match (self.order_type, self.side) {
+            (OrderType::Market, _) => Some(current_price_data.open),
+            (OrderType::Limit, Side::Long) | (OrderType::StopEntry, Side::Short) => match current_price_data.low <= self.price {
+                true => Some(self.price.min(current_price_data.open)),
+                false => None
+            },
+            (OrderType::Limit, Side::Short) | (OrderType::StopEntry, Side::Long) => match current_price_data.high >= self.price {
+                true => Some(self.price.max(current_price_data.open)),
+                false => None
+            }
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.periods_open >= self.expiry_periods
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClosedOrder {
+    pub order: Order,
+    pub close_reason: OrderCloseReason,
+    pub close_time: u64
+}
+
+impl ClosedOrder {
+    pub fn new(order: Order, close_reason: OrderCloseReason, close_time: u64) -> ClosedOrder {
+        ClosedOrder {
+            order,
+            close_reason,
+            close_time
+        }
+    }
+}
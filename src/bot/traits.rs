@@ -1,9 +1,16 @@
 use crate::config;
 use config::Config;
+use crate::bot::orders::OrderType;
+use crate::asset::Asset;
 use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::distributions::uniform::SampleUniform;
+use crate::strategy::mutation::MutationOperator;
 
-#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+// Up bots only go long, Down bots only go short and Both bots can do either
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
 pub enum Direction {
+    #[default]
     Up,
     Down,
     Both
@@ -20,13 +27,47 @@ pub struct Traits {
     pub minimum_holding_periods: u64,
     pub maximum_holding_periods: u64,
     pub percent_purchase: f64,
-    pub target_sell_percentage: f64
+    pub target_sell_percentage: f64,
+    #[serde(default)]
+    pub direction: Direction,
+    #[serde(default)]
+    pub order_type: OrderType,
+    #[serde(default)]
+    pub entry_offset_percentage: f64,
+    #[serde(default = "default_order_expiry_periods")]
+    pub order_expiry_periods: u64,
+    #[serde(default = "default_leverage")]
+    pub leverage: f64,
+    #[serde(default = "default_asset")]
+    pub asset: Asset, // the asset the bot trades
+    #[serde(default)]
+    pub step_sizes: [f64; NUMBER_OF_STEP_SIZES] // self adaptive mutation steps of the number genes in the order they are declared
 }
 
-impl Traits {
-    pub fn new(config: &Config) -> Traits {
-        let mut rng = rand::thread_rng();
+pub const NUMBER_OF_STEP_SIZES: usize = 12;
+
+fn default_order_expiry_periods() -> u64 {
+    1
+}
 
+fn default_leverage() -> f64 {
+    1.0
+}
+
+fn default_asset() -> Asset {
+    Asset::ETH
+}
+
+// newer genes are allowed to be fixed to a single value by setting min and max to the same value
+pub fn gen_range_or_min<T: SampleUniform + PartialOrd + Copy, R: Rng>(rng: &mut R, min: T, max: T) -> T {
+    match min < max {
+        true => rng.gen_range(min, max),
+        false => min
+    }
+}
+
+impl Traits {
+    pub fn new<R: Rng>(rng: &mut R, config: &Config) -> Traits {
         let number_of_averaging_periods = rng.gen_range(config.traits.number_of_averaging_periods.min, config.traits.number_of_averaging_periods.max);
         let minimum_buy_momentum = rng.gen_range(config.traits.minimum_buy_momentum.min, config.traits.minimum_buy_momentum.max);
         let maximum_buy_momentum = rng.gen_range(config.traits.maximum_buy_momentum.min, config.traits.maximum_buy_momentum.max);
@@ -36,6 +77,13 @@ impl Traits {
         let maximum_holding_periods = rng.gen_range(config.traits.maximum_holding_periods.min, config.traits.maximum_holding_periods.max);
         let percent_purchase = rng.gen_range(config.traits.percent_purchase.min, config.traits.percent_purchase.max);
         let target_sell_percentage = rng.gen_range(config.traits.target_sell_percentage.min, config.traits.target_sell_percentage.max);
+        let direction = *config.traits.direction.allowed().choose(rng).unwrap();
+        let order_type = *config.traits.order_type.allowed().choose(rng).unwrap();
+        let entry_offset_percentage = gen_range_or_min(rng, config.traits.entry_offset_percentage.min, config.traits.entry_offset_percentage.max);
+        let order_expiry_periods = gen_range_or_min(rng, config.traits.order_expiry_periods.min, config.traits.order_expiry_periods.max);
+        let leverage = gen_range_or_min(rng, config.traits.leverage.min, config.traits.leverage.max);
+        let asset = *config.traits.asset.allowed().choose(rng).unwrap();
+        let step_sizes = [config.mutation.initial_step_size(); NUMBER_OF_STEP_SIZES];
 
         Traits {
             number_of_averaging_periods,
@@ -46,48 +94,95 @@ impl Traits {
             minimum_holding_periods,
             maximum_holding_periods,
             percent_purchase,
-            target_sell_percentage
+            target_sell_percentage,
+            direction,
+            order_type,
+            entry_offset_percentage,
+            order_expiry_periods,
+            leverage,
+            asset,
+            step_sizes
         }
     }
 
-    pub fn mutate<R: Rng>(&mut self, rng: &mut R, config: &Config) {
+    // the number genes are moved by the configured mutation operator, the rest are redrawn
+    pub fn mutate<R: Rng>(&mut self, rng: &mut R, config: &Config, generation: u64) {
+        let mutation_chance = config.mutation_chance * config.mutation.annealing_scale(generation);
+        self.mutate_with_chance(rng, config, generation, mutation_chance);
+    }
+
+    pub fn mutate_with_chance<R: Rng>(&mut self, rng: &mut R, config: &Config, generation: u64, mutation_chance: f64) {
+        let mutation = &config.mutation;
+
+        let step_sizes = match mutation.operator {
+            MutationOperator::SelfAdaptive => {
+                mutation.adapt_step_sizes(&mut self.step_sizes, rng);
+                self.step_sizes
+            },
+            _ => [mutation.step_size(generation); NUMBER_OF_STEP_SIZES]
+        };
+
         // for each trait, see if we need to regenerate based on mutation chance
-        self.number_of_averaging_periods = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.number_of_averaging_periods.min, config.traits.number_of_averaging_periods.max),
+        self.number_of_averaging_periods = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_u64(self.number_of_averaging_periods, config.traits.number_of_averaging_periods.min, config.traits.number_of_averaging_periods.max, step_sizes[0], rng),
             false => self.number_of_averaging_periods
         };
-        self.minimum_buy_momentum = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.minimum_buy_momentum.min, config.traits.minimum_buy_momentum.max),
+        self.minimum_buy_momentum = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.minimum_buy_momentum, config.traits.minimum_buy_momentum.min, config.traits.minimum_buy_momentum.max, step_sizes[1], rng),
             false => self.minimum_buy_momentum
         };
-        self.maximum_buy_momentum = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.maximum_buy_momentum.min, config.traits.maximum_buy_momentum.max),
+        self.maximum_buy_momentum = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.maximum_buy_momentum, config.traits.maximum_buy_momentum.min, config.traits.maximum_buy_momentum.max, step_sizes[2], rng),
             false => self.maximum_buy_momentum
         };
-        self.trailing_stop_loss = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.trailing_stop_loss.min, config.traits.trailing_stop_loss.max),
+        self.trailing_stop_loss = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.trailing_stop_loss, config.traits.trailing_stop_loss.min, config.traits.trailing_stop_loss.max, step_sizes[3], rng),
             false => self.trailing_stop_loss
         };
-        self.stop_loss = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.stop_loss.min, config.traits.stop_loss.max),
+        self.stop_loss = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.stop_loss, config.traits.stop_loss.min, config.traits.stop_loss.max, step_sizes[4], rng),
             false => self.stop_loss
         };
-        self.minimum_holding_periods = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.minimum_holding_periods.min, config.traits.minimum_holding_periods.max),
+        self.minimum_holding_periods = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_u64(self.minimum_holding_periods, config.traits.minimum_holding_periods.min, config.traits.minimum_holding_periods.max, step_sizes[5], rng),
             false => self.minimum_holding_periods
         };
-        self.maximum_holding_periods = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.maximum_holding_periods.min, config.traits.maximum_holding_periods.max),
+        self.maximum_holding_periods = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_u64(self.maximum_holding_periods, config.traits.maximum_holding_periods.min, config.traits.maximum_holding_periods.max, step_sizes[6], rng),
             false => self.maximum_holding_periods
         };
-        self.percent_purchase = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.percent_purchase.min, config.traits.percent_purchase.max),
+        self.percent_purchase = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.percent_purchase, config.traits.percent_purchase.min, config.traits.percent_purchase.max, step_sizes[7], rng),
             false => self.percent_purchase
         };
-        self.target_sell_percentage = match rng.gen_bool(config.mutation_chance) {
-            true => rng.gen_range(config.traits.target_sell_percentage.min, config.traits.target_sell_percentage.max),
+        self.target_sell_percentage = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.target_sell_percentage, config.traits.target_sell_percentage.min, config.traits.target_sell_percentage.max, step_sizes[8], rng),
             false => self.target_sell_percentage
         };
+        self.direction = match rng.gen_bool(mutation_chance) {
+            true => *config.traits.direction.allowed().choose(rng).unwrap(),
+            false => self.direction
+        };
+        self.order_type = match rng.gen_bool(mutation_chance) {
+            true => *config.traits.order_type.allowed().choose(rng).unwrap(),
+            false => self.order_type
+        };
+        self.entry_offset_percentage = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.entry_offset_percentage, config.traits.entry_offset_percentage.min, config.traits.entry_offset_percentage.max, step_sizes[9], rng),
+            false => self.entry_offset_percentage
+        };
+        self.order_expiry_periods = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_u64(self.order_expiry_periods, config.traits.order_expiry_periods.min, config.traits.order_expiry_periods.max, step_sizes[10], rng),
+            false => self.order_expiry_periods
+        };
+        self.leverage = match rng.gen_bool(mutation_chance) {
+            true => mutation.mutate_f64(self.leverage, config.traits.leverage.min, config.traits.leverage.max, step_sizes[11], rng),
+            false => self.leverage
+        };
+        self.asset = match rng.gen_bool(mutation_chance) {
+            true => *config.traits.asset.allowed().choose(rng).unwrap(),
+            false => self.asset
+        };
     }
 }
 
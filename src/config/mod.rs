@@ -1,5 +1,24 @@
 extern crate serde;
 use std::fmt;
+use std::collections::HashMap;
+use crate::strategy::{Strategy, Momentum};
+use crate::market::slippage::Slippage;
+use crate::market::volume::VolumeConstraint;
+use crate::market::fees::FeeSchedule;
+use crate::market::margin::Margin;
+use crate::market::rules::TradingRules;
+use crate::asset::Asset;
+use crate::bot::fitness::FitnessFunction;
+use crate::bot::constraints::ActivityConstraints;
+use crate::simulation::nsga2::Nsga2;
+use crate::simulation::islands::Islands;
+use crate::simulation::speciation::Speciation;
+use crate::simulation::diversity::Diversity;
+use crate::simulation::stopping::StoppingCriteria;
+use crate::simulation::hall_of_fame::HallOfFameConfig;
+use crate::simulation::selection::Selection;
+use crate::strategy::crossover::Crossover;
+use crate::strategy::mutation::Mutation;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigError {
@@ -22,92 +41,179 @@ impl fmt::Display for ConfigError {
     }
 }
 
+// How stop losses are executed against a period
+// Close only checks stops against the close of the period and sells at the close (the original behaviour)
+// IntraCandle stops out as soon as the low/high trades through the stop and sells at the stop price
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum ExecutionModel {
+    #[default]
+    Close,
+    IntraCandle
+}
+
+// Which exit fills first when a period hits both the targeted sell price and a stop
+// Optimistic always assumes the target filled first (the original behaviour), Pessimistic assumes the stop did
+// OpenToClose infers the path from the candle, a candle that closes above its open is assumed to have hit its low before its high
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum IntrabarOrdering {
+    #[default]
+    Optimistic,
+    Pessimistic,
+    OpenToClose
+}
+
+fn default_sample_every_periods() -> u64 {
+    1
+}
+
+// Records the value of the bot at the close of every sampled period instead of only on periods something sells
+// Sampling every n periods keeps the generation files from getting too big, the final period is always recorded
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct Config {
-    pub traits: traits::Traits,
+pub struct EquityCurve {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_sample_every_periods")]
+    pub sample_every_periods: u64
+}
+
+impl Default for EquityCurve {
+    fn default() -> EquityCurve {
+        EquityCurve {
+            enabled: false,
+            sample_every_periods: default_sample_every_periods()
+        }
+    }
+}
+
+fn default_quote_asset() -> Asset {
+    Asset::USD
+}
+
+fn default_benchmark_asset() -> Asset {
+    Asset::ETH
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", bound = "")]
+pub struct Config<S: Strategy = Momentum> {
+    pub traits: S::GenomeConfig,
     pub number_of_bots: u64,
     pub number_of_generations: u64,
     pub starting_money: f64,
     pub minimum_purchase_size: f64,
     pub transaction_fee_as_percentage: f64,
+    // fee charged every period on the value of the borrowed asset of a short position
+    #[serde(default)]
+    pub short_borrow_fee_as_percentage: f64,
+    #[serde(default)]
+    pub execution_model: ExecutionModel,
+    #[serde(default)]
+    pub intrabar_ordering: IntrabarOrdering,
+    #[serde(default)]
+    pub slippage: Slippage,
+    #[serde(default)]
+    pub volume_constraint: VolumeConstraint,
+    #[serde(default)]
+    pub fee_schedule: Option<FeeSchedule>, // replaces the flat transaction fee when set
+    #[serde(default)]
+    pub margin: Margin,
+    #[serde(default = "default_quote_asset")]
+    pub quote_asset: Asset, // the currency bots hold their money in, prices, fees and sizes are all in it
+    #[serde(default)]
+    pub trading_rules: HashMap<Asset, TradingRules>,
+    #[serde(default)]
+    pub equity_curve: EquityCurve,
+    #[serde(default = "default_benchmark_asset")]
+    pub benchmark_asset: Asset, // bots are compared against buying and holding it over the run
+    #[serde(default)]
+    pub fitness_function: FitnessFunction,
+    #[serde(default)]
+    pub activity_constraints: ActivityConstraints,
+    #[serde(default)]
+    pub nsga2: Nsga2,
+    #[serde(default)]
+    pub selection: Selection,
+    #[serde(default)]
+    pub crossover: Crossover,
+    #[serde(default)]
+    pub mutation: Mutation,
+    #[serde(default)]
+    pub islands: Islands,
+    #[serde(default)]
+    pub speciation: Speciation,
+    #[serde(default)]
+    pub diversity: Diversity,
+    #[serde(default)]
+    pub stopping_criteria: StoppingCriteria,
+    #[serde(default)]
+    pub hall_of_fame: HallOfFameConfig,
     pub number_of_threads: u64,
     pub mutation_chance: f64,
     pub hamming: f64,
     pub elite_bot_carry_over: u64
 }
 
-impl Config {
-    pub fn validate_config(&self) -> Vec<ConfigError> {
-        let mut config_errors = Vec::<ConfigError>::new();
-
-        if self.traits.number_of_averaging_periods.max < self.traits.number_of_averaging_periods.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.NumberOfAveragingPeriods.Max".to_string()));
-        }
-
-        if self.traits.minimum_buy_momentum.max < self.traits.minimum_buy_momentum.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MinimumBuyMomentum.Max".to_string()));
-        }
-
-        if self.traits.maximum_buy_momentum.max < self.traits.maximum_buy_momentum.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MaximumBuyMomentum.Max".to_string()));
-        }
-
-        if self.traits.trailing_stop_loss.max < self.traits.trailing_stop_loss.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.TrailingStopLoss.Max".to_string()));
-        }
-
-        // max will never be less then 0.0 because max cannot be less then min
-        if self.traits.trailing_stop_loss.min < 0.0 {
-            config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.TrailingStopLoss.Min".to_string()));
-        }
-
-        if self.traits.stop_loss.max < self.traits.stop_loss.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.StopLoss.Max".to_string()));
-        }
-
-        if self.traits.stop_loss.min < 0.0 {
-            config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.StopLoss.Min".to_string()));
-        }
+impl<S: Strategy> Config<S> {
+    // assets without their own rules use the default ones
+    pub fn trading_rules_for(&self, asset: Asset) -> TradingRules {
+        self.trading_rules.get(&asset).copied().unwrap_or_default()
+    }
 
-        if self.traits.minimum_holding_periods.max < self.traits.minimum_holding_periods.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MinimumHoldingPeriods.Max".to_string()));
-        }
+    pub fn validate_config(&self) -> Vec<ConfigError> {
+        let mut config_errors = S::validate_config(&self.traits);
 
-        if self.traits.maximum_holding_periods.max < self.traits.maximum_holding_periods.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MaximumHoldingPeriods.Max".to_string()));
+        if self.minimum_purchase_size < 0.0 {
+            config_errors.push(ConfigError::new("Minimum Purchase Size cannot be less then 0".to_string(), "MinimumPurchaseSize".to_string()));
         }
 
-        if self.traits.percent_purchase.max < self.traits.percent_purchase.min {
-            config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.PercentPurchase.Max".to_string()));
+        if self.starting_money < 0.0 {
+            config_errors.push(ConfigError::new("Starting Money cannot be less then 0".to_string(), "StartingMoney".to_string()));
         }
 
-        if self.traits.percent_purchase.min < 0.0 {
-            config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.PercentPurchase.Min".to_string()));
+        if self.transaction_fee_as_percentage > 1.0 || self.transaction_fee_as_percentage < 0.0 {
+            config_errors.push(ConfigError::new("Transaction Fee As Percentage can only be from 0 to 1".to_string(), "TransactionFeeAsPercentage".to_string()));
         }
 
-        if self.traits.percent_purchase.max > 100.0 {
-            config_errors.push(ConfigError::new("Max cannot be greater then 100".to_string(), "Traits.PercentPurchase.Max".to_string()));
+        if self.short_borrow_fee_as_percentage > 1.0 || self.short_borrow_fee_as_percentage < 0.0 {
+            config_errors.push(ConfigError::new("Short Borrow Fee As Percentage can only be from 0 to 1".to_string(), "ShortBorrowFeeAsPercentage".to_string()));
         }
 
-        if self.minimum_purchase_size < 0.0 {
-            config_errors.push(ConfigError::new("Minimum Purchase Size cannot be less then 0".to_string(), "MinimumPurchaseSize".to_string()));
+        config_errors.append(&mut self.slippage.validate());
+        config_errors.append(&mut self.volume_constraint.validate());
+        if let Some(fee_schedule) = &self.fee_schedule {
+            config_errors.append(&mut fee_schedule.validate());
         }
-
-        if self.starting_money < 0.0 {
-            config_errors.push(ConfigError::new("Starting Money cannot be less then 0".to_string(), "StartingMoney".to_string()));
+        config_errors.append(&mut self.margin.validate());
+        if self.equity_curve.sample_every_periods == 0 {
+            config_errors.push(ConfigError::new("Sample Every Periods must be at least 1".to_string(), "EquityCurve.SampleEveryPeriods".to_string()));
         }
 
-        if self.transaction_fee_as_percentage > 1.0 || self.transaction_fee_as_percentage < 0.0 {
-            config_errors.push(ConfigError::new("Transaction Fee As Percentage can only be from 0 to 1".to_string(), "TransactionFeeAsPercentage".to_string()));
+        config_errors.append(&mut self.fitness_function.validate(self.equity_curve.enabled));
+        config_errors.append(&mut self.activity_constraints.validate());
+        config_errors.append(&mut self.nsga2.validate());
+        config_errors.append(&mut self.selection.validate());
+        config_errors.append(&mut self.crossover.validate());
+        config_errors.append(&mut self.mutation.validate());
+        config_errors.append(&mut self.islands.validate(self.number_of_bots, self.elite_bot_carry_over));
+        config_errors.append(&mut self.speciation.validate());
+        config_errors.append(&mut self.diversity.validate());
+        config_errors.append(&mut self.stopping_criteria.validate());
+        let number_of_bred_bots = self.number_of_bots.saturating_sub(self.elite_bot_carry_over * self.islands.number_of_islands);
+        config_errors.append(&mut self.hall_of_fame.validate(number_of_bred_bots));
+
+        // the quote currency's prices are only used to convert values, bots can't trade it against itself
+        if S::tradeable_assets(&self.traits).contains(&self.quote_asset) {
+            config_errors.push(ConfigError::new("Traits.Asset can not allow the Quote Asset".to_string(), "Traits.Asset".to_string()));
         }
 
-        if self.traits.target_sell_percentage.min <= 0.0 {
-            config_errors.push(ConfigError::new("Min must be greater then 0".to_string(), "Traits.TargetedSellPrice.Min".to_string()));
+        // there is no USD price data, only the quote currency can be held as is
+        if self.benchmark_asset == Asset::USD && self.quote_asset != Asset::USD {
+            config_errors.push(ConfigError::new("Benchmark Asset can only be USD when it's the Quote Asset".to_string(), "BenchmarkAsset".to_string()));
         }
 
-        if self.traits.target_sell_percentage.min > self.traits.target_sell_percentage.max {
-            config_errors.push(ConfigError::new("Min must be less then Max".to_string(), "Traits.TargetSellPercentage.Min".to_string()));
+        for (asset, trading_rules) in &self.trading_rules {
+            config_errors.append(&mut trading_rules.validate(*asset));
         }
 
         if self.mutation_chance < 0.0 || self.mutation_chance > 1.0 {
@@ -122,11 +228,16 @@ impl Config {
             config_errors.push(ConfigError::new("Number of elite bot carry over must be less then the number of bots".to_string(), "EliteBotCarryOver".to_string()));
         }
 
-        return config_errors;
+        config_errors
     }
 }
 
-mod traits {
+pub mod traits {
+    use super::ConfigError;
+    use crate::bot::traits::Direction as DirectionGene;
+    use crate::bot::orders::OrderType as OrderTypeGene;
+    use crate::asset::Asset as AssetGene;
+
     #[derive(Debug, Serialize, Deserialize)]
     #[serde(rename_all = "PascalCase")]
     pub struct Traits {
@@ -138,7 +249,120 @@ mod traits {
         pub minimum_holding_periods: MinimumHoldingPeriods,
         pub maximum_holding_periods: MaximumHoldingPeriods,
         pub percent_purchase: PercentPurchase,
-        pub target_sell_percentage: TargetSellPercentage
+        pub target_sell_percentage: TargetSellPercentage,
+        #[serde(default)]
+        pub direction: Direction,
+        #[serde(default)]
+        pub order_type: OrderType,
+        #[serde(default)]
+        pub entry_offset_percentage: EntryOffsetPercentage,
+        #[serde(default)]
+        pub order_expiry_periods: OrderExpiryPeriods,
+        #[serde(default)]
+        pub leverage: Leverage,
+        #[serde(default)]
+        pub asset: Asset
+    }
+
+    impl Traits {
+        pub fn validate(&self) -> Vec<ConfigError> {
+            let mut config_errors = Vec::<ConfigError>::new();
+
+            if self.number_of_averaging_periods.max < self.number_of_averaging_periods.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.NumberOfAveragingPeriods.Max".to_string()));
+            }
+
+            if self.minimum_buy_momentum.max < self.minimum_buy_momentum.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MinimumBuyMomentum.Max".to_string()));
+            }
+
+            if self.maximum_buy_momentum.max < self.maximum_buy_momentum.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MaximumBuyMomentum.Max".to_string()));
+            }
+
+            if self.trailing_stop_loss.max < self.trailing_stop_loss.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.TrailingStopLoss.Max".to_string()));
+            }
+
+            // max will never be less then 0.0 because max cannot be less then min
+            if self.trailing_stop_loss.min < 0.0 {
+                config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.TrailingStopLoss.Min".to_string()));
+            }
+
+            if self.stop_loss.max < self.stop_loss.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.StopLoss.Max".to_string()));
+            }
+
+            if self.stop_loss.min < 0.0 {
+                config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.StopLoss.Min".to_string()));
+            }
+
+            if self.minimum_holding_periods.max < self.minimum_holding_periods.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MinimumHoldingPeriods.Max".to_string()));
+            }
+
+            if self.maximum_holding_periods.max < self.maximum_holding_periods.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.MaximumHoldingPeriods.Max".to_string()));
+            }
+
+            if self.percent_purchase.max < self.percent_purchase.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.PercentPurchase.Max".to_string()));
+            }
+
+            if self.percent_purchase.min < 0.0 {
+                config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.PercentPurchase.Min".to_string()));
+            }
+
+            if self.percent_purchase.max > 100.0 {
+                config_errors.push(ConfigError::new("Max cannot be greater then 100".to_string(), "Traits.PercentPurchase.Max".to_string()));
+            }
+
+            if self.target_sell_percentage.min <= 0.0 {
+                config_errors.push(ConfigError::new("Min must be greater then 0".to_string(), "Traits.TargetedSellPrice.Min".to_string()));
+            }
+
+            if self.target_sell_percentage.min > self.target_sell_percentage.max {
+                config_errors.push(ConfigError::new("Min must be less then Max".to_string(), "Traits.TargetSellPercentage.Min".to_string()));
+            }
+
+            if self.direction.allowed().is_empty() {
+                config_errors.push(ConfigError::new("At least one direction must be allowed".to_string(), "Traits.Direction".to_string()));
+            }
+
+            if self.order_type.allowed().is_empty() {
+                config_errors.push(ConfigError::new("At least one order type must be allowed".to_string(), "Traits.OrderType".to_string()));
+            }
+
+            if self.entry_offset_percentage.max < self.entry_offset_percentage.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.EntryOffsetPercentage.Max".to_string()));
+            }
+
+            if self.entry_offset_percentage.min < 0.0 {
+                config_errors.push(ConfigError::new("Min cannot be less then 0".to_string(), "Traits.EntryOffsetPercentage.Min".to_string()));
+            }
+
+            if self.order_expiry_periods.max < self.order_expiry_periods.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.OrderExpiryPeriods.Max".to_string()));
+            }
+
+            if self.order_expiry_periods.min < 1 {
+                config_errors.push(ConfigError::new("Min must be at least 1".to_string(), "Traits.OrderExpiryPeriods.Min".to_string()));
+            }
+
+            if self.leverage.max < self.leverage.min {
+                config_errors.push(ConfigError::new("Max cannot be less then Min".to_string(), "Traits.Leverage.Max".to_string()));
+            }
+
+            if self.leverage.min < 1.0 {
+                config_errors.push(ConfigError::new("Min must be at least 1".to_string(), "Traits.Leverage.Min".to_string()));
+            }
+
+            if self.asset.allowed().is_empty() {
+                config_errors.push(ConfigError::new("At least one asset must be allowed".to_string(), "Traits.Asset".to_string()));
+            }
+
+            config_errors
+        }
     }
 
     #[derive(Debug, Serialize, Deserialize)]
@@ -203,4 +427,159 @@ mod traits {
         pub min: f64,
         pub max: f64
     }
+
+    // Which direction genes a bot is allowed to have, by default bots only go long
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct Direction {
+        pub up: bool,
+        pub down: bool,
+        pub both: bool
+    }
+
+    impl Default for Direction {
+        fn default() -> Direction {
+            Direction {
+                up: true,
+                down: false,
+                both: false
+            }
+        }
+    }
+
+    impl Direction {
+        pub fn allowed(&self) -> Vec<DirectionGene> {
+            let mut allowed = Vec::<DirectionGene>::new();
+
+            if self.up {
+                allowed.push(DirectionGene::Up);
+            }
+
+            if self.down {
+                allowed.push(DirectionGene::Down);
+            }
+
+            if self.both {
+                allowed.push(DirectionGene::Both);
+            }
+
+            allowed
+        }
+    }
+
+    // Which order types bots are allowed to use, by default bots only use market orders
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct OrderType {
+        pub market: bool,
+        pub limit: bool,
+        pub stop_entry: bool
+    }
+
+    impl Default for OrderType {
+        fn default() -> OrderType {
+            OrderType {
+                market: true,
+                limit: false,
+                stop_entry: false
+            }
+        }
+    }
+
+    impl OrderType {
+        pub fn allowed(&self) -> Vec<OrderTypeGene> {
+            let mut allowed = Vec::<OrderTypeGene>::new();
+
+            if self.market {
+                allowed.push(OrderTypeGene::Market);
+            }
+
+            if self.limit {
+                allowed.push(OrderTypeGene::Limit);
+            }
+
+            if self.stop_entry {
+                allowed.push(OrderTypeGene::StopEntry);
+            }
+
+            allowed
+        }
+    }
+
+    // How far away from the open limit and stop entry orders are placed
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct EntryOffsetPercentage {
+        pub min: f64,
+        pub max: f64
+    }
+
+    // How many periods a limit or stop entry order stays open for before it expires
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct OrderExpiryPeriods {
+        pub min: u64,
+        pub max: u64
+    }
+
+    impl Default for OrderExpiryPeriods {
+        fn default() -> OrderExpiryPeriods {
+            OrderExpiryPeriods {
+                min: 1,
+                max: 1
+            }
+        }
+    }
+
+    // How many times the money put up a position is, by default bots don't borrow
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct Leverage {
+        pub min: f64,
+        pub max: f64
+    }
+
+    impl Default for Leverage {
+        fn default() -> Leverage {
+            Leverage {
+                min: 1.0,
+                max: 1.0
+            }
+        }
+    }
+
+    // Which assets bots are allowed to trade, by default bots only trade ETH
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct Asset {
+        #[serde(rename = "BTC")]
+        pub btc: bool,
+        #[serde(rename = "ETH")]
+        pub eth: bool
+    }
+
+    impl Default for Asset {
+        fn default() -> Asset {
+            Asset {
+                btc: false,
+                eth: true
+            }
+        }
+    }
+
+    impl Asset {
+        pub fn allowed(&self) -> Vec<AssetGene> {
+            let mut allowed = Vec::<AssetGene>::new();
+
+            if self.btc {
+                allowed.push(AssetGene::BTC);
+            }
+
+            if self.eth {
+                allowed.push(AssetGene::ETH);
+            }
+
+            allowed
+        }
+    }
 }
@@ -4,5 +4,7 @@ pub mod asset;
 pub mod price_data;
 pub mod bot;
 pub mod config;
+pub mod strategy;
+pub mod market;
 #[macro_use]
 extern crate serde_derive;
\ No newline at end of file
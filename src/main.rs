@@ -4,12 +4,15 @@ pub mod asset;
 pub mod price_data;
 pub mod bot;
 pub mod config;
+pub mod strategy;
+pub mod market;
 use config::Config;
 use regex::Regex;
 #[macro_use]
 extern crate serde_derive;
 extern crate alphanumeric_sort;
 use crate::simulation::Simulation;
+use crate::asset::Asset;
 use chrono::{Timelike, Utc, Datelike};
 use std::thread;
 use std::fs;
@@ -22,13 +25,21 @@ struct SimulationResponse {
     id: String
 }
 
+fn get_price_history_path(asset: Asset) -> &'static str {
+    match asset {
+        Asset::BTC => "./historicalData/bitcoinPriceData.json",
+        Asset::ETH => "./historicalData/etherumPriceData.json",
+        Asset::USD => unreachable!("USD is what the bots trade with and has no price history")
+    }
+}
+
 #[get("/health")]
 async fn health() -> impl Responder {
     HttpResponse::Ok().body("Healthy")
 }
 
 #[get("/configs/{config_name}")]
-async fn get_config(web::Path(config_name): web::Path<(String)>) -> impl Responder {
+async fn get_config(web::Path(config_name): web::Path<String>) -> impl Responder {
     let file_path = "./configs/".to_owned() + config_name.as_str() + ".yaml";
 
     let config_as_yaml_result = fs::read_to_string(file_path);
@@ -38,7 +49,7 @@ async fn get_config(web::Path(config_name): web::Path<(String)>) -> impl Respond
     }
 
     let config_as_yaml = config_as_yaml_result.unwrap();
-    let config: Config = serde_yaml::from_str(&config_as_yaml.as_str()).unwrap();
+    let config: Config = serde_yaml::from_str(config_as_yaml.as_str()).unwrap();
     let config_as_json = serde_json::to_string(&config).unwrap();
 
     HttpResponse::Ok()
@@ -50,12 +61,13 @@ async fn get_config(web::Path(config_name): web::Path<(String)>) -> impl Respond
 async fn list_configs() -> impl Responder {
     let entries = fs::read_dir("./configs").unwrap();
 
+    let directory_regex = Regex::new("./configs\\\\(.*).yaml").unwrap();
+
     let mut config_names = Vec::<String>::new();
     for entry in entries {
         let directory_buffer = entry.unwrap().path();
         let file_path = directory_buffer.to_str().unwrap();
 
-        let directory_regex = Regex::new("./configs\\\\(.*).yaml").unwrap();
         let captures = directory_regex.captures(file_path).unwrap();
         let config_name = &captures[1];
 
@@ -68,7 +80,7 @@ async fn list_configs() -> impl Responder {
 }
 
 #[put("/configs/{config_name}")]
-async fn create_or_update_config(config_web_request: web::Json<Config>, web::Path(config_name): web::Path<(String)>) -> impl Responder {
+async fn create_or_update_config(config_web_request: web::Json<Config>, web::Path(config_name): web::Path<String>) -> impl Responder {
     let config = config_web_request.into_inner();
     let file_path = "./configs/".to_owned() + config_name.as_str() + ".yaml";
 
@@ -83,7 +95,7 @@ async fn create_or_update_config(config_web_request: web::Json<Config>, web::Pat
 #[get("/configs/form")]
 async fn get_config_form() -> impl Responder {
     let config_form_as_yaml = fs::read_to_string("./config_form.yaml").unwrap();
-    let config_form: serde_yaml::Value = serde_yaml::from_str(&config_form_as_yaml.as_str()).unwrap();
+    let config_form: serde_yaml::Value = serde_yaml::from_str(config_form_as_yaml.as_str()).unwrap();
     let config_as_json = serde_json::to_string(&config_form).unwrap();
 
     HttpResponse::Ok()
@@ -95,13 +107,13 @@ async fn get_config_form() -> impl Responder {
 async fn validate_config(config: web::Json<Config>) -> impl Responder {
     let config_errors = config.into_inner().validate_config();
 
-    if config_errors.len() > 0 {
+    if !config_errors.is_empty() {
         return HttpResponse::BadRequest()
             .content_type("application/json")
             .body(serde_json::to_string(&config_errors).unwrap());
     }
 
-    return HttpResponse::Ok()
+    HttpResponse::Ok()
         .body("")
 }
 
@@ -110,7 +122,7 @@ async fn start_simulation(config_web: web::Json<Config>) -> impl Responder {
     let config = config_web.into_inner();
     let config_errors = config.validate_config();
 
-    if config_errors.len() > 0 {
+    if !config_errors.is_empty() {
         return HttpResponse::BadRequest()
             .content_type("application/json")
             .body(serde_json::to_string(&config_errors).unwrap());
@@ -126,7 +138,22 @@ async fn start_simulation(config_web: web::Json<Config>) -> impl Responder {
         current_time_in_utc.minute(),
         current_time_in_utc.second()
     );
-    let mut simulation = Simulation::web_create("./historicalData/etherumPriceData.json", config, id.clone()).unwrap();
+    // only the assets the bots are allowed to trade are loaded along with the quote currency to convert them
+    // validation keeps the quote currency out of the allowed assets
+    let mut paths_to_price_histories: Vec<(Asset, &str)> = config.traits.asset.allowed()
+        .iter()
+        .map(|asset| (*asset, get_price_history_path(*asset)))
+        .collect();
+    if config.quote_asset != Asset::USD {
+        paths_to_price_histories.push((config.quote_asset, get_price_history_path(config.quote_asset)));
+    }
+    // the benchmark is loaded too so bots can be compared against holding it
+    let benchmark_loaded = paths_to_price_histories.iter().any(|(asset, _)| *asset == config.benchmark_asset);
+    if config.benchmark_asset != Asset::USD && !benchmark_loaded {
+        paths_to_price_histories.push((config.benchmark_asset, get_price_history_path(config.benchmark_asset)));
+    }
+
+    let mut simulation = Simulation::web_create(&paths_to_price_histories, config, id.clone()).unwrap();
     thread::spawn(move || {
         simulation.start_simulation();
     });
@@ -144,12 +171,13 @@ async fn start_simulation(config_web: web::Json<Config>) -> impl Responder {
 async fn list_simulations() -> impl Responder {
     let entries = fs::read_dir("./simulations").unwrap();
 
+    let directory_regex = Regex::new("./simulations\\\\").unwrap();
+
     let mut ids = Vec::<String>::new();
     for entry in entries {
         let directory_buffer = entry.unwrap().path();
         let directory_as_str = directory_buffer.to_str().unwrap();
 
-        let directory_regex = Regex::new("./simulations\\\\").unwrap();
         let sim_id_result = directory_regex.replace_all(directory_as_str, "");
 
         let id = sim_id_result.into_owned();
@@ -163,7 +191,7 @@ async fn list_simulations() -> impl Responder {
 }
 
 #[get("/simulations/{simulation_id}/generations")]
-async fn list_generations(web::Path(simulation_id): web::Path<(String)>) -> impl Responder {
+async fn list_generations(web::Path(simulation_id): web::Path<String>) -> impl Responder {
     let directory = "./simulations/".to_owned() + simulation_id.as_str() + "/results";
 
     let read_dir_result = fs::read_dir(&directory);
@@ -226,6 +254,21 @@ async fn get_simulation_status(web::Path(simulation_id): web::Path<String>) -> i
         .body(status_result.unwrap())
 }
 
+#[get("/simulations/{simulation_id}/hall_of_fame")]
+async fn get_hall_of_fame(web::Path(simulation_id): web::Path<String>) -> impl Responder {
+    let hall_of_fame_path = "./simulations/".to_owned() + simulation_id.as_str() + "/hall_of_fame.json";
+    let hall_of_fame_result = fs::read_to_string(hall_of_fame_path);
+
+    if hall_of_fame_result.is_err() {
+        return HttpResponse::NotFound()
+            .body("");
+    }
+
+    HttpResponse::Ok()
+        .content_type("application/json")
+        .body(hall_of_fame_result.unwrap())
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     HttpServer::new(|| {
@@ -244,6 +287,7 @@ async fn main() -> std::io::Result<()> {
             .service(list_generations)
             .service(get_generation)
             .service(get_simulation_status)
+            .service(get_hall_of_fame)
     })
     .bind("127.0.0.1:8080")?
     .run()
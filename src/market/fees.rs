@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+use crate::config::ConfigError;
+
+const SECONDS_IN_A_DAY: u64 = 24 * 60 * 60;
+
+// Makers rest on the book (limit orders and targeted sells), takers trade against it
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Liquidity {
+    Maker,
+    Taker
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FeeTier {
+    pub minimum_volume: f64, // rolling traded volume needed to reach the tier
+    pub maker_fee_as_percentage: f64,
+    pub taker_fee_as_percentage: f64
+}
+
+fn default_volume_window_days() -> u64 {
+    30
+}
+
+// Exchange style fee schedule, the tier is picked from the volume traded over the window before the trade
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct FeeSchedule {
+    pub tiers: Vec<FeeTier>,
+    #[serde(default)]
+    pub minimum_fee: f64,
+    #[serde(default = "default_volume_window_days")]
+    pub volume_window_days: u64
+}
+
+impl FeeSchedule {
+    // tiers are in ascending order of volume so the last one reached is the one applied
+    pub fn tier(&self, traded_volume: f64) -> usize {
+        self.tiers
+            .iter()
+            .rposition(|tier| traded_volume >= tier.minimum_volume)
+            .unwrap_or(0)
+    }
+
+    pub fn fee(&self, notional: f64, liquidity: Liquidity, traded_volume: f64) -> (f64, usize) {
+        let tier_index = self.tier(traded_volume);
+        let tier = &self.tiers[tier_index];
+
+        let fee_as_percentage = match liquidity {
+            Liquidity::Maker => tier.maker_fee_as_percentage,
+            Liquidity::Taker => tier.taker_fee_as_percentage
+        };
+
+        ((notional * fee_as_percentage).max(self.minimum_fee), tier_index)
+    }
+
+    pub fn window_in_seconds(&self) -> u64 {
+        self.volume_window_days * SECONDS_IN_A_DAY
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.tiers.is_empty() {
+            config_errors.push(ConfigError::new("Fee Schedule needs at least one tier".to_string(), "FeeSchedule.Tiers".to_string()));
+        }
+
+        for (index, tier) in self.tiers.iter().enumerate() {
+            let path = format!("FeeSchedule.Tiers.{}", index);
+
+            if tier.minimum_volume < 0.0 {
+                config_errors.push(ConfigError::new("Minimum Volume can not be negative".to_string(), format!("{}.MinimumVolume", path)));
+            }
+
+            if tier.maker_fee_as_percentage < 0.0 || tier.taker_fee_as_percentage < 0.0 {
+                config_errors.push(ConfigError::new("Fees can not be negative".to_string(), path.clone()));
+            }
+
+            if index > 0 && tier.minimum_volume <= self.tiers[index - 1].minimum_volume {
+                config_errors.push(ConfigError::new("Tiers must be in ascending order of Minimum Volume".to_string(), format!("{}.MinimumVolume", path)));
+            }
+        }
+
+        if self.minimum_fee < 0.0 {
+            config_errors.push(ConfigError::new("Minimum Fee can not be negative".to_string(), "FeeSchedule.MinimumFee".to_string()));
+        }
+
+        if self.volume_window_days == 0 {
+            config_errors.push(ConfigError::new("Volume Window Days must be greater than 0".to_string(), "FeeSchedule.VolumeWindowDays".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+// The notional a bot has traded, kept so the fee tier can be worked out from the rolling window
+#[derive(Debug, Default)]
+pub struct TradedVolume {
+    trades: VecDeque<(u64, f64)>
+}
+
+impl TradedVolume {
+    pub fn record(&mut self, time: u64, notional: f64) {
+        self.trades.push_back((time, notional));
+    }
+
+    // drops trades that have fallen out of the window and returns what is left
+    pub fn rolling_volume(&mut self, time: u64, window_in_seconds: u64) -> f64 {
+        while let Some((trade_time, _)) = self.trades.front() {
+            if trade_time + window_in_seconds > time {
+                break;
+            }
+            self.trades.pop_front();
+        }
+
+        self.trades.iter().map(|(_, notional)| notional).sum()
+    }
+}
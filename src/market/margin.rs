@@ -0,0 +1,28 @@
+use crate::config::ConfigError;
+
+// Margin accounting for leveraged positions
+// A position is liquidated once its equity falls below the maintenance margin of its current notional
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Margin {
+    #[serde(default)]
+    pub maintenance_margin_as_percentage: f64,
+    #[serde(default)]
+    pub borrow_interest_as_percentage: f64 // interest charged on the borrowed money each period
+}
+
+impl Margin {
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.maintenance_margin_as_percentage < 0.0 || self.maintenance_margin_as_percentage >= 1.0 {
+            config_errors.push(ConfigError::new("Maintenance Margin must be at least 0 and less then 1".to_string(), "Margin.MaintenanceMarginAsPercentage".to_string()));
+        }
+
+        if self.borrow_interest_as_percentage < 0.0 {
+            config_errors.push(ConfigError::new("Borrow Interest can not be negative".to_string(), "Margin.BorrowInterestAsPercentage".to_string()));
+        }
+
+        config_errors
+    }
+}
@@ -0,0 +1,5 @@
+pub mod slippage;
+pub mod volume;
+pub mod fees;
+pub mod margin;
+pub mod rules;
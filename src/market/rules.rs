@@ -0,0 +1,136 @@
+use crate::asset::Asset;
+use crate::bot::holdings::Side;
+use crate::config::ConfigError;
+
+// stops values that are a whole number of steps being floored a step short by float error
+const STEP_TOLERANCE: f64 = 1e-9;
+
+// amounts were always kept to four decimal places before lot steps
+fn round_to_four_places(amount: f64) -> f64 {
+    (amount * 10000.0).round() / 10000.0
+}
+
+fn round_down_to_four_places(amount: f64) -> f64 {
+    (amount * 10000.0).floor() / 10000.0
+}
+
+// The exchange rules for trading an asset, amounts are in the asset and prices and notionals in the quote currency
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct TradingRules {
+    #[serde(default)]
+    pub lot_step: Option<f64>, // amounts are rounded down to a multiple of this, without it they are rounded to four decimal places
+    #[serde(default)]
+    pub price_tick: Option<f64>, // order prices are rounded to a multiple of this
+    #[serde(default)]
+    pub minimum_notional: f64, // smallest amount * price an order can be
+    #[serde(default)]
+    pub maximum_order_size: Option<f64> // largest amount a single order can be
+}
+
+impl Default for TradingRules {
+    fn default() -> TradingRules {
+        TradingRules {
+            lot_step: None,
+            price_tick: None,
+            minimum_notional: 0.0,
+            maximum_order_size: None
+        }
+    }
+}
+
+impl TradingRules {
+    fn round_down_to_lot_step(&self, amount: f64) -> f64 {
+        match self.lot_step {
+            Some(lot_step) => ((amount / lot_step) + STEP_TOLERANCE).floor() * lot_step,
+            None => round_down_to_four_places(amount)
+        }
+    }
+
+    // the largest amount the rules allow that is no more then the amount given
+    // without a lot step it's the amount to the nearest four decimal places
+    pub fn round_amount(&self, amount: f64) -> f64 {
+        let amount = amount.min(self.maximum_order_size.unwrap_or(f64::INFINITY));
+
+        match self.lot_step {
+            Some(_) => self.round_down_to_lot_step(amount),
+            None => round_to_four_places(amount)
+        }
+    }
+
+    // how much of an amount can be traded when only so much is available
+    // with a lot step the whole amount is traded as is when it fits so no dust is left behind
+    pub fn amount_that_fits(&self, amount: f64, amount_available: f64) -> f64 {
+        let amount_available = amount_available.min(self.maximum_order_size.unwrap_or(f64::INFINITY));
+
+        match (self.lot_step, amount <= amount_available) {
+            (Some(_), true) => amount,
+            (Some(_), false) => self.round_down_to_lot_step(amount_available),
+            (None, _) => amount.min(round_down_to_four_places(amount_available))
+        }
+    }
+
+    pub fn round_price(&self, price: f64) -> f64 {
+        match self.price_tick {
+            Some(price_tick) => (price / price_tick).round() * price_tick,
+            None => price
+        }
+    }
+
+    fn round_price_up(&self, price: f64) -> f64 {
+        match self.price_tick {
+            Some(price_tick) => ((price / price_tick) - STEP_TOLERANCE).ceil() * price_tick,
+            None => price
+        }
+    }
+
+    fn round_price_down(&self, price: f64) -> f64 {
+        match self.price_tick {
+            Some(price_tick) => ((price / price_tick) + STEP_TOLERANCE).floor() * price_tick,
+            None => price
+        }
+    }
+
+    // a target is a resting order on the far side of the price so it's moved away from the price to the next tick
+    pub fn round_target_price(&self, price: f64, side: Side) -> f64 {
+        match side {
+            Side::Long => self.round_price_up(price),
+            Side::Short => self.round_price_down(price)
+        }
+    }
+
+    // a stop is moved away from the price to the next tick so it never triggers early
+    pub fn round_stop_price(&self, price: f64, side: Side) -> f64 {
+        match side {
+            Side::Long => self.round_price_down(price),
+            Side::Short => self.round_price_up(price)
+        }
+    }
+
+    pub fn meets_minimum_notional(&self, notional: f64) -> bool {
+        notional >= self.minimum_notional
+    }
+
+    pub fn validate(&self, asset: Asset) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+        let path = format!("TradingRules.{:?}", asset);
+
+        if self.lot_step.is_some_and(|lot_step| lot_step <= 0.0) {
+            config_errors.push(ConfigError::new("Lot Step must be greater then 0".to_string(), format!("{}.LotStep", path)));
+        }
+
+        if self.price_tick.is_some_and(|price_tick| price_tick <= 0.0) {
+            config_errors.push(ConfigError::new("Price Tick must be greater then 0".to_string(), format!("{}.PriceTick", path)));
+        }
+
+        if self.minimum_notional < 0.0 {
+            config_errors.push(ConfigError::new("Minimum Notional can not be negative".to_string(), format!("{}.MinimumNotional", path)));
+        }
+
+        if self.maximum_order_size.is_some_and(|maximum_order_size| maximum_order_size <= 0.0 || maximum_order_size < self.lot_step.unwrap_or(0.0)) {
+            config_errors.push(ConfigError::new("Maximum Order Size must be greater then 0 and at least the Lot Step".to_string(), format!("{}.MaximumOrderSize", path)));
+        }
+
+        config_errors
+    }
+}
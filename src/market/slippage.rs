@@ -0,0 +1,80 @@
+use crate::bot::holdings::Side;
+use crate::config::ConfigError;
+use crate::price_data::PriceData;
+
+// None fills at the candle price, FixedBps moves every fill by a fixed number of basis points,
+// SpreadFraction pays a fraction of a fixed bid/ask spread and VolatilityScaled pays a fraction of the candle's range
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum SlippageModel {
+    #[default]
+    None,
+    FixedBps,
+    SpreadFraction,
+    VolatilityScaled
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Fill {
+    Entry,
+    Exit
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Slippage {
+    #[serde(default)]
+    pub model: SlippageModel,
+    #[serde(default)]
+    pub bps: f64,
+    #[serde(default)]
+    pub spread_percentage: f64, // the full bid/ask spread as a percentage of the price
+    #[serde(default)]
+    pub spread_fraction: f64, // how much of the spread we pay, 0.5 is crossing from the mid to the bid or ask
+    #[serde(default)]
+    pub volatility_multiplier: f64
+}
+
+impl Slippage {
+    // the fraction of the price lost to slippage on a fill during the period
+    pub fn fraction(&self, current_price_data: &PriceData) -> f64 {
+        match self.model {
+            SlippageModel::None => 0.0,
+            SlippageModel::FixedBps => self.bps / 10000.0,
+            SlippageModel::SpreadFraction => (self.spread_percentage / 100.0) * self.spread_fraction,
+            SlippageModel::VolatilityScaled => self.volatility_multiplier * ((current_price_data.high - current_price_data.low) / current_price_data.open)
+        }
+    }
+
+    // buys fill above the price and sells fill below it
+    // entering a long and exiting a short are buys, exiting a long and entering a short are sells
+    pub fn apply(&self, price: f64, side: Side, fill: Fill, current_price_data: &PriceData) -> f64 {
+        let fraction = self.fraction(current_price_data);
+
+        match (side, fill) {
+            (Side::Long, Fill::Entry) | (Side::Short, Fill::Exit) => price * (1.0 + fraction),
+            (Side::Long, Fill::Exit) | (Side::Short, Fill::Entry) => price * (1.0 - fraction)
+        }
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.bps < 0.0 {
+            config_errors.push(ConfigError::new("Bps cannot be less then 0".to_string(), "Slippage.Bps".to_string()));
+        }
+
+        if self.spread_percentage < 0.0 {
+            config_errors.push(ConfigError::new("Spread Percentage cannot be less then 0".to_string(), "Slippage.SpreadPercentage".to_string()));
+        }
+
+        if self.spread_fraction < 0.0 || self.spread_fraction > 1.0 {
+            config_errors.push(ConfigError::new("Spread Fraction must be between 0 and 1".to_string(), "Slippage.SpreadFraction".to_string()));
+        }
+
+        if self.volatility_multiplier < 0.0 {
+            config_errors.push(ConfigError::new("Volatility Multiplier cannot be less then 0".to_string(), "Slippage.VolatilityMultiplier".to_string()));
+        }
+
+        config_errors
+    }
+}
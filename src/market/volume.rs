@@ -0,0 +1,43 @@
+use crate::config::ConfigError;
+use crate::price_data::PriceData;
+
+// What happens to the part of an order that could not fill because the period ran out of volume
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum UnfilledRemainder {
+    #[default]
+    KeepOpen,
+    Cancel
+}
+
+// Caps how much of a period's volume a bot is allowed to trade
+// Exits that run out of volume keep the rest of the holding open and try again next period
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct VolumeConstraint {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub participation_rate: f64,
+    #[serde(default)]
+    pub unfilled_remainder: UnfilledRemainder
+}
+
+impl VolumeConstraint {
+    // the amount of the asset a bot can trade during the period
+    pub fn available_volume(&self, current_price_data: &PriceData) -> f64 {
+        match self.enabled {
+            true => current_price_data.volume * self.participation_rate,
+            false => f64::INFINITY
+        }
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.participation_rate < 0.0 || self.participation_rate > 1.0 {
+            config_errors.push(ConfigError::new("Participation Rate must be between 0 and 1".to_string(), "VolumeConstraint.ParticipationRate".to_string()));
+        }
+
+        config_errors
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use crate::asset::Asset;
+use super::MarketHistory;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineValue {
+    pub time: u64,
+    pub value: f64
+}
+
+// Buying the benchmark asset with all the starting money at the open of the first period and holding it
+// Bots are compared against it over the same times, the value is taken at the close of each period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Baseline {
+    pub asset: Asset,
+    pub starting_money: f64,
+    pub history: Vec<BaselineValue>,
+    #[serde(skip)]
+    positions: HashMap<u64, usize> // where each time is in the history
+}
+
+impl Baseline {
+    // holding the quote currency is just holding the money
+    // nothing can be worked out for an asset that wasn't loaded
+    pub fn new(market_history: &MarketHistory, asset: Asset, starting_money: f64) -> Option<Baseline> {
+        let history: Vec<BaselineValue> = match market_history.price_history(asset) {
+            Some(price_history) => {
+                let amount = starting_money / price_history.first()?.open;
+
+                price_history
+                    .iter()
+                    .map(|price_data| BaselineValue { time: price_data.time, value: amount * price_data.close })
+                    .collect()
+            },
+            None if asset == market_history.quote_asset() => (0..market_history.len() as u64)
+                .map(|period| BaselineValue { time: market_history.time(period).unwrap(), value: starting_money })
+                .collect(),
+            None => return None
+        };
+
+        let positions = history
+            .iter()
+            .enumerate()
+            .map(|(position, baseline_value)| (baseline_value.time, position))
+            .collect();
+
+        Some(Baseline {
+            asset,
+            starting_money,
+            history,
+            positions
+        })
+    }
+
+    pub fn value_at(&self, time: u64) -> Option<f64> {
+        self.positions
+            .get(&time)
+            .map(|position| self.history[*position].value)
+    }
+
+    // the baseline at the same points as an equity curve of the starting money, values at the given times and the final money
+    pub fn equity(&self, times: &[u64], end_time: u64) -> Option<Vec<f64>> {
+        let mut equity = vec!(self.starting_money);
+        for time in times {
+            equity.push(self.value_at(*time)?);
+        }
+        equity.push(self.value_at(end_time)?);
+
+        Some(equity)
+    }
+}
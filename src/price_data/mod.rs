@@ -1,4 +1,8 @@
+pub mod baseline;
 extern crate serde;
+use std::collections::{HashMap, HashSet};
+use crate::asset::Asset;
+use baseline::Baseline;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PriceData {
@@ -8,4 +12,148 @@ pub struct PriceData {
     pub open: f64,
     pub close: f64,
     pub volume: f64
-}
\ No newline at end of file
+}
+
+// Prices the asset in the quote currency instead of USD, e.g. ETH/USD and BTC/USD become ETH/BTC
+// the extremes of the two assets aren't known to line up so the low and high are the widest the cross rate could have reached
+fn convert_to_quote(price_data: &PriceData, quote_price_data: &PriceData) -> PriceData {
+    let open = price_data.open / quote_price_data.open;
+    let close = price_data.close / quote_price_data.close;
+
+    PriceData {
+        time: price_data.time,
+        low: (price_data.low / quote_price_data.high).min(open).min(close),
+        high: (price_data.high / quote_price_data.low).max(open).max(close),
+        open,
+        close,
+        volume: price_data.volume
+    }
+}
+
+// One price series per asset, aligned so the same period in every series is the same point in time
+// Every series is priced in the quote currency, the quote's own USD series is kept to report values in USD
+#[derive(Debug)]
+pub struct MarketHistory {
+    price_histories: HashMap<Asset, Vec<PriceData>>,
+    quote_asset: Asset,
+    quote_price_history: Option<Vec<PriceData>>,
+    number_of_periods: usize,
+    baseline: Option<Baseline>
+}
+
+impl MarketHistory {
+    // the price histories are all in USD and only the times every asset has price data for are kept
+    // unless the quote currency is USD its price history has to be included so the others can be converted
+    pub fn new(mut price_histories: HashMap<Asset, Vec<PriceData>>, quote_asset: Asset) -> MarketHistory {
+        let mut common_times: Option<HashSet<u64>> = None;
+        for price_history in price_histories.values() {
+            let times: HashSet<u64> = price_history.iter().map(|price_data| price_data.time).collect();
+
+            common_times = match common_times {
+                Some(common_times) => Some(common_times.intersection(&times).copied().collect()),
+                None => Some(times)
+            };
+        }
+        let common_times = common_times.unwrap_or_default();
+
+        for price_history in price_histories.values_mut() {
+            price_history.retain(|price_data| common_times.contains(&price_data.time));
+            price_history.sort_by_key(|price_data| price_data.time);
+            price_history.dedup_by_key(|price_data| price_data.time);
+        }
+
+        let quote_price_history = price_histories.remove(&quote_asset);
+        if let Some(quote_price_history) = &quote_price_history {
+            for price_history in price_histories.values_mut() {
+                *price_history = price_history
+                    .iter()
+                    .zip(quote_price_history.iter())
+                    .map(|(price_data, quote_price_data)| convert_to_quote(price_data, quote_price_data))
+                    .collect();
+            }
+        }
+
+        MarketHistory {
+            number_of_periods: common_times.len(),
+            price_histories,
+            quote_asset,
+            quote_price_history,
+            baseline: None
+        }
+    }
+
+    // worked out once so every bot is compared against the same buy and hold run
+    pub fn set_baseline(&mut self, asset: Asset, starting_money: f64) {
+        self.baseline = Baseline::new(self, asset, starting_money);
+    }
+
+    pub fn baseline(&self) -> Option<&Baseline> {
+        self.baseline.as_ref()
+    }
+
+    pub fn quote_asset(&self) -> Asset {
+        self.quote_asset
+    }
+
+    // what one unit of the quote currency is worth in USD at the open of the period
+    pub fn quote_in_usd_at_open(&self, period: u64) -> f64 {
+        match &self.quote_price_history {
+            Some(quote_price_history) => quote_price_history[period as usize].open,
+            None => 1.0
+        }
+    }
+
+    pub fn quote_in_usd_at_close(&self, period: u64) -> f64 {
+        match &self.quote_price_history {
+            Some(quote_price_history) => quote_price_history[period as usize].close,
+            None => 1.0
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.number_of_periods
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.number_of_periods == 0
+    }
+
+    pub fn assets(&self) -> impl Iterator<Item = &Asset> {
+        self.price_histories.keys()
+    }
+
+    pub fn price_history(&self, asset: Asset) -> Option<&[PriceData]> {
+        self.price_histories.get(&asset).map(|price_history| price_history.as_slice())
+    }
+
+    pub fn price_data(&self, asset: Asset, period: u64) -> Option<&PriceData> {
+        self.price_histories.get(&asset)?.get(period as usize)
+    }
+
+    // every series shares the same times so any of them can be used
+    pub fn time(&self, period: u64) -> Option<u64> {
+        self.price_histories
+            .values()
+            .chain(self.quote_price_history.iter())
+            .next()?
+            .get(period as usize)
+            .map(|price_data| price_data.time)
+    }
+}
+
+// A single ETH/USD series, used as is so it's expected to already be in time order
+impl From<Vec<PriceData>> for MarketHistory {
+    fn from(price_history: Vec<PriceData>) -> MarketHistory {
+        let mut price_histories = HashMap::new();
+        let number_of_periods = price_history.len();
+        price_histories.insert(Asset::ETH, price_history);
+
+        MarketHistory {
+            price_histories,
+            quote_asset: Asset::USD,
+            quote_price_history: None,
+            number_of_periods,
+            baseline: None
+        }
+    }
+}
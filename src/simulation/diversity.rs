@@ -0,0 +1,142 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::bot::Bot;
+use crate::config::{Config, ConfigError};
+use crate::strategy::Strategy;
+
+// What is done to the population when it has lost its diversity
+// Immigrants replaces bots with new random bots, Hypermutation mutates the bots with the much higher hypermutation chance
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum DiversityAction {
+    #[default]
+    Immigrants,
+    Hypermutation
+}
+
+fn default_threshold() -> f64 {
+    5.0
+}
+
+fn default_replacement_percentage() -> f64 {
+    20.0
+}
+
+fn default_hypermutation_chance() -> f64 {
+    0.5
+}
+
+// Watches the average hamming distance between the bots of each generation
+// when it drops below the threshold a percentage of the next generation is changed, elite bots are never touched
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Diversity {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_threshold")]
+    pub threshold: f64, // on the same scale as Hamming
+    #[serde(default)]
+    pub action: DiversityAction,
+    #[serde(default = "default_replacement_percentage")]
+    pub replacement_percentage: f64,
+    #[serde(default = "default_hypermutation_chance")]
+    pub hypermutation_chance: f64 // the chance of each gene of a hypermutated bot changing, in place of the mutation chance
+}
+
+impl Default for Diversity {
+    fn default() -> Diversity {
+        Diversity {
+            enabled: false,
+            threshold: default_threshold(),
+            action: DiversityAction::Immigrants,
+            replacement_percentage: default_replacement_percentage(),
+            hypermutation_chance: default_hypermutation_chance()
+        }
+    }
+}
+
+// What was done to the bots bred from a generation
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Intervention {
+    pub action: DiversityAction,
+    pub diversity: f64,
+    pub number_of_bots: u64
+}
+
+impl Diversity {
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.threshold < 0.0 || self.threshold > 100.0 {
+            config_errors.push(ConfigError::new("Threshold must be between 0 and 100".to_string(), "Diversity.Threshold".to_string()));
+        }
+
+        if self.replacement_percentage <= 0.0 || self.replacement_percentage > 100.0 {
+            config_errors.push(ConfigError::new("Replacement Percentage must be greater then 0 and at most 100".to_string(), "Diversity.ReplacementPercentage".to_string()));
+        }
+
+        if self.hypermutation_chance < 0.0 || self.hypermutation_chance > 1.0 {
+            config_errors.push(ConfigError::new("Hypermutation Chance must be between 0 and 1".to_string(), "Diversity.HypermutationChance".to_string()));
+        }
+
+        config_errors
+    }
+
+    // the intervention a generation with this diversity needs, if any
+    pub fn check(&self, diversity: f64, number_of_bots: u64) -> Option<Intervention> {
+        if !self.enabled || diversity >= self.threshold {
+            return None;
+        }
+
+        Some(Intervention {
+            action: self.action,
+            diversity,
+            number_of_bots: (number_of_bots as f64 * (self.replacement_percentage / 100.0)).ceil() as u64
+        })
+    }
+}
+
+// the average hamming distance between every pair of bots
+pub fn diversity<S: Strategy>(bots: &[Bot<S>]) -> f64 {
+    if bots.len() < 2 {
+        return 0.0;
+    }
+
+    let mut total_distance = 0.0;
+    for (position, bot_one) in bots.iter().enumerate() {
+        for bot_two in &bots[position + 1..] {
+            total_distance += bot_one.hamming(bot_two);
+        }
+    }
+
+    let number_of_pairs = (bots.len() * (bots.len() - 1)) / 2;
+    total_distance / number_of_pairs as f64
+}
+
+// changes random bots of a newly bred generation, the elite bots at the start of every island and migrants are left alone
+// the changed bots keep their id and island, returns how many were changed
+pub fn intervene<S: Strategy, R: Rng>(bots: &mut [Bot<S>], intervention: &Intervention, config: &Config<S>, generation: u64, rng: &mut R) -> u64 {
+    let positions: Vec<usize> = config.islands
+        .replaceable_positions(bots, config.elite_bot_carry_over)
+        .concat()
+        .choose_multiple(rng, intervention.number_of_bots as usize)
+        .copied()
+        .collect();
+
+    for position in &positions {
+        let bot = &bots[*position];
+        let mut new_bot = match intervention.action {
+            DiversityAction::Immigrants => Bot::new(config, bot.id),
+            DiversityAction::Hypermutation => {
+                let mut traits = bot.traits;
+                S::mutate_with_chance(&mut traits, rng, config, generation, config.diversity.hypermutation_chance);
+                Bot::from_traits(traits, config, bot.id)
+            }
+        };
+        new_bot.island = bot.island;
+
+        bots[*position] = new_bot;
+    }
+
+    positions.len() as u64
+}
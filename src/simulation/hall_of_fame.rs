@@ -0,0 +1,145 @@
+use crate::bot::Bot;
+use crate::bot::fitness::Metrics;
+use crate::config::{Config, ConfigError};
+use crate::strategy::Strategy;
+
+fn default_size() -> u64 {
+    10
+}
+
+// Keeps the best distinct genomes seen over the whole run so they aren't lost once they are bred out
+// Genomes within MinimumDistance hamming of each other are the same genome, only the best of them is kept
+// ReinjectedBots of them replace the last bred bots of every generation that didn't migrate
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct HallOfFameConfig {
+    #[serde(default = "default_size")]
+    pub size: u64,
+    #[serde(default)]
+    pub minimum_distance: f64,
+    #[serde(default)]
+    pub reinjected_bots: u64
+}
+
+impl Default for HallOfFameConfig {
+    fn default() -> HallOfFameConfig {
+        HallOfFameConfig {
+            size: default_size(),
+            minimum_distance: 0.0,
+            reinjected_bots: 0
+        }
+    }
+}
+
+impl HallOfFameConfig {
+    // the bred bots are the bots of a generation that aren't elite bots
+    pub fn validate(&self, number_of_bred_bots: u64) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.minimum_distance < 0.0 || self.minimum_distance > 100.0 {
+            config_errors.push(ConfigError::new("Minimum Distance must be between 0 and 100".to_string(), "HallOfFame.MinimumDistance".to_string()));
+        }
+
+        if self.reinjected_bots > self.size {
+            config_errors.push(ConfigError::new("Reinjected Bots can not be more then the Size".to_string(), "HallOfFame.ReinjectedBots".to_string()));
+        }
+
+        if self.reinjected_bots > number_of_bred_bots {
+            config_errors.push(ConfigError::new("Reinjected Bots can not replace more then the bred bots".to_string(), "HallOfFame.ReinjectedBots".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct HallOfFameEntry<S: Strategy> {
+    pub generation: u64,
+    pub bot_id: u64, // the id the bot had in its generation
+    pub fitness: f64,
+    pub metrics: Metrics,
+    pub traits: S::Genome
+}
+
+// best first
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct HallOfFame<S: Strategy> {
+    pub entries: Vec<HallOfFameEntry<S>>
+}
+
+impl<S: Strategy> HallOfFame<S> {
+    pub fn new() -> HallOfFame<S> {
+        HallOfFame {
+            entries: Vec::new()
+        }
+    }
+
+    // adds the bots of a generation, disqualified bots are never let in
+    // a bot and every genome in the hall of fame close to it are merged into the best of them
+    pub fn update(&mut self, bots: &[Bot<S>], generation: u64, config: &HallOfFameConfig) {
+        for bot in bots.iter().filter(|bot| !bot.disqualified) {
+            let entry = HallOfFameEntry {
+                generation,
+                bot_id: bot.id,
+                fitness: bot.fitness,
+                metrics: bot.metrics,
+                traits: bot.traits
+            };
+
+            let (same_genome, mut entries): (Vec<HallOfFameEntry<S>>, Vec<HallOfFameEntry<S>>) = self.entries
+                .drain(..)
+                .partition(|existing| S::distance(&existing.traits, &bot.traits) <= config.minimum_distance);
+
+            let best_existing = same_genome.into_iter().max_by(|a, b| a.fitness.total_cmp(&b.fitness));
+            match best_existing {
+                Some(existing) if existing.fitness >= bot.fitness => entries.push(existing),
+                _ => entries.push(entry)
+            }
+            self.entries = entries;
+
+            self.entries.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+            self.entries.truncate(config.size as usize);
+        }
+    }
+
+    // the best genomes not already in a newly bred generation replace its last bred bots, taken from each island in turn
+    // migrants are left alone, the hall of fame bots keep the id and island of the bot they replace, returns how many were put back
+    pub fn reinject(&self, bots: &mut [Bot<S>], config: &Config<S>) -> u64 {
+        let mut bred_positions = config.islands.replaceable_positions(bots, config.elite_bot_carry_over);
+        let mut reinjected = 0;
+
+        for entry in &self.entries {
+            if reinjected >= config.hall_of_fame.reinjected_bots {
+                break;
+            }
+
+            if bots.iter().any(|bot| bot.traits == entry.traits) {
+                continue;
+            }
+
+            // an island without bred bots left passes its turn on
+            let island = reinjected as usize % bred_positions.len();
+            let position = bred_positions[island].pop().or_else(|| bred_positions.iter_mut().find_map(|positions| positions.pop()));
+            let Some(position) = position else {
+                break;
+            };
+
+            let bot = &bots[position];
+            let mut hall_of_fame_bot = Bot::from_traits(entry.traits, config, bot.id);
+            hall_of_fame_bot.island = bot.island;
+
+            bots[position] = hall_of_fame_bot;
+            reinjected += 1;
+        }
+
+        reinjected
+    }
+}
+
+impl<S: Strategy> Default for HallOfFame<S> {
+    fn default() -> HallOfFame<S> {
+        HallOfFame::new()
+    }
+}
@@ -0,0 +1,228 @@
+use crate::bot::Bot;
+use crate::config::{Config, ConfigError};
+use crate::strategy::Strategy;
+use super::breed_population;
+
+// Which islands the best bots of an island migrate to
+// Ring sends them on to the next island, FullyConnected sends them to every other island
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Topology {
+    #[default]
+    Ring,
+    FullyConnected
+}
+
+fn default_number_of_islands() -> u64 {
+    1
+}
+
+fn default_migration_interval() -> u64 {
+    5
+}
+
+fn default_number_of_migrants() -> u64 {
+    1
+}
+
+// Splits the bots into islands that are bred apart from each other so one good genome can't take over the whole population
+// Every MigrationInterval generations copies of the best bots of each island replace the worst children of the islands they migrate to
+// One island is the original single population
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Islands {
+    #[serde(default = "default_number_of_islands")]
+    pub number_of_islands: u64,
+    #[serde(default = "default_migration_interval")]
+    pub migration_interval: u64,
+    #[serde(default = "default_number_of_migrants")]
+    pub number_of_migrants: u64, // how many bots each island sends to every island it migrates to
+    #[serde(default)]
+    pub topology: Topology
+}
+
+impl Default for Islands {
+    fn default() -> Islands {
+        Islands {
+            number_of_islands: default_number_of_islands(),
+            migration_interval: default_migration_interval(),
+            number_of_migrants: default_number_of_migrants(),
+            topology: Topology::Ring
+        }
+    }
+}
+
+// How an island did in a generation
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IslandStats {
+    pub island: u64,
+    pub number_of_bots: u64,
+    pub best_fitness: Option<f64>, // of the bots that weren't disqualified, none if there weren't any
+    pub average_fitness: Option<f64>,
+    pub migrants: u64 // bots that arrived from another island this generation
+}
+
+impl Islands {
+    // the bots are spread as evenly as possible, the first islands take any left over
+    pub fn island_sizes(&self, number_of_bots: u64) -> Vec<u64> {
+        (0..self.number_of_islands)
+            .map(|island| (number_of_bots / self.number_of_islands) + u64::from(island < number_of_bots % self.number_of_islands))
+            .collect()
+    }
+
+    pub fn destinations(&self, island: u64) -> Vec<u64> {
+        if self.number_of_islands < 2 {
+            return Vec::new();
+        }
+
+        match self.topology {
+            Topology::Ring => vec!((island + 1) % self.number_of_islands),
+            Topology::FullyConnected => (0..self.number_of_islands).filter(|destination| *destination != island).collect()
+        }
+    }
+
+    // how many migrants every island takes in each migration
+    pub fn incoming_migrants(&self) -> u64 {
+        match self.topology {
+            Topology::Ring => self.number_of_migrants,
+            Topology::FullyConnected => self.number_of_migrants * self.number_of_islands.saturating_sub(1)
+        }
+    }
+
+    // migration happens after the bots of the generation have been run
+    pub fn is_migration_generation(&self, generation: u64) -> bool {
+        self.number_of_islands > 1 && generation.is_multiple_of(self.migration_interval)
+    }
+
+    // puts the bots on their islands in order, ids are their position in the population
+    pub fn assign<S: Strategy>(&self, bots: &mut [Bot<S>]) {
+        let sizes = self.island_sizes(bots.len() as u64);
+        let mut bots = bots.iter_mut();
+
+        for (island, size) in sizes.into_iter().enumerate() {
+            for bot in bots.by_ref().take(size as usize) {
+                bot.island = island as u64;
+            }
+        }
+    }
+
+    // where the bred bots of each island are in a population, everything after the elite bots at the start of the island
+    pub fn bred_positions(&self, number_of_bots: u64, elite_bot_carry_over: u64) -> Vec<Vec<usize>> {
+        let mut island_start = 0;
+
+        self.island_sizes(number_of_bots)
+            .into_iter()
+            .map(|size| {
+                let positions = ((island_start + elite_bot_carry_over).min(island_start + size) as usize)..((island_start + size) as usize);
+                island_start += size;
+                positions.collect()
+            })
+            .collect()
+    }
+
+    // the bred positions that can be changed after breeding, the migrants that replaced bred bots are left alone
+    pub fn replaceable_positions<S: Strategy>(&self, bots: &[Bot<S>], elite_bot_carry_over: u64) -> Vec<Vec<usize>> {
+        self.bred_positions(bots.len() as u64, elite_bot_carry_over)
+            .into_iter()
+            .map(|positions| positions.into_iter().filter(|position| bots[*position].migrated_from.is_none()).collect())
+            .collect()
+    }
+
+    pub fn stats<S: Strategy>(&self, bots: &[Bot<S>]) -> Vec<IslandStats> {
+        (0..self.number_of_islands)
+            .map(|island| {
+                let island_bots: Vec<&Bot<S>> = bots.iter().filter(|bot| bot.island == island).collect();
+                let qualified_fitness: Vec<f64> = island_bots.iter().filter(|bot| !bot.disqualified).map(|bot| bot.fitness).collect();
+                let total_fitness: f64 = qualified_fitness.iter().sum();
+
+                IslandStats {
+                    island,
+                    number_of_bots: island_bots.len() as u64,
+                    best_fitness: match qualified_fitness.is_empty() {
+                        true => None,
+                        false => Some(qualified_fitness.iter().copied().fold(f64::NEG_INFINITY, f64::max))
+                    },
+                    average_fitness: match qualified_fitness.is_empty() {
+                        true => None,
+                        false => Some(total_fitness / qualified_fitness.len() as f64)
+                    },
+                    migrants: island_bots.iter().filter(|bot| bot.migrated_from.is_some()).count() as u64
+                }
+            })
+            .collect()
+    }
+
+    pub fn validate(&self, number_of_bots: u64, elite_bot_carry_over: u64) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.number_of_islands < 1 {
+            config_errors.push(ConfigError::new("Number of Islands must be at least 1".to_string(), "Islands.NumberOfIslands".to_string()));
+            return config_errors;
+        }
+
+        if self.migration_interval < 1 {
+            config_errors.push(ConfigError::new("Migration Interval must be at least 1".to_string(), "Islands.MigrationInterval".to_string()));
+        }
+
+        // every island needs two bots to breed and room for its elite bots
+        let smallest_island = number_of_bots / self.number_of_islands;
+        if self.number_of_islands > 1 && (smallest_island < 2 || elite_bot_carry_over >= smallest_island) {
+            config_errors.push(ConfigError::new("Every island needs at least two bots and more bots then the elite bot carry over".to_string(), "Islands.NumberOfIslands".to_string()));
+        }
+
+        if self.number_of_islands > 1 && self.incoming_migrants() > smallest_island.saturating_sub(elite_bot_carry_over) {
+            config_errors.push(ConfigError::new("Number of Migrants can not replace more then the bred bots of an island".to_string(), "Islands.NumberOfMigrants".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+// the bots are expected to be sorted best first, every island is bred on its own and keeps its size
+// the generation is the one the new bots will be run in
+pub fn breed_islands<S: Strategy>(bots: Vec<Bot<S>>, config: &Config<S>, generation: u64, partner_fallbacks: &mut u64) -> Vec<Bot<S>> {
+    let islands = &config.islands;
+    let sizes = islands.island_sizes(config.number_of_bots);
+
+    let mut populations: Vec<Vec<Bot<S>>> = (0..islands.number_of_islands).map(|_| Vec::new()).collect();
+    for bot in bots {
+        let island = bot.island.min(islands.number_of_islands - 1) as usize;
+        populations[island].push(bot);
+    }
+
+    let mut new_populations: Vec<Vec<Bot<S>>> = populations
+        .iter()
+        .zip(&sizes)
+        .map(|(population, size)| match population.is_empty() {
+            // an island can only be empty if its bots were lost, it starts again from random bots
+            true => (0..*size).map(|id| Bot::new(config, id)).collect(),
+            false => breed_population(population, config, generation, *size, partner_fallbacks)
+        })
+        .collect();
+
+    // the best bots of each island replace the last children bred on the islands they migrate to
+    if islands.is_migration_generation(generation - 1) {
+        let mut replaced = vec!(0usize; populations.len());
+        for (island, population) in populations.iter().enumerate() {
+            for destination in islands.destinations(island as u64) {
+                for migrant in population.iter().take(islands.number_of_migrants as usize) {
+                    let new_population = &mut new_populations[destination as usize];
+                    let position = new_population.len() - 1 - replaced[destination as usize];
+
+                    let mut migrated_bot = migrant.create_clone(config, 0);
+                    migrated_bot.migrated_from = Some(island as u64);
+                    new_population[position] = migrated_bot;
+                    replaced[destination as usize] += 1;
+                }
+            }
+        }
+    }
+
+    let mut new_bots: Vec<Bot<S>> = new_populations.into_iter().flatten().collect();
+    for (id, bot) in new_bots.iter_mut().enumerate() {
+        bot.id = id as u64;
+    }
+    islands.assign(&mut new_bots);
+
+    new_bots
+}
@@ -1,27 +1,48 @@
+pub mod nsga2;
+pub mod selection;
+pub mod islands;
+pub mod speciation;
+pub mod diversity;
+pub mod stopping;
+pub mod hall_of_fame;
 use std::vec::Vec;
 use std::fs;
 use serde_json;
-use serde_yaml;
 use std::error::Error;
 use crate::bot::Bot;
-use crate::price_data::{PriceData};
+use crate::price_data::{PriceData, MarketHistory};
+use crate::price_data::baseline::Baseline;
+use crate::asset::Asset;
 use crate::config::Config;
+use crate::strategy::Strategy;
+use selection::ParentSelection;
+use nsga2::Objective;
+use islands::IslandStats;
+use speciation::Species;
+use diversity::Intervention;
+use stopping::{Progress, StopReason};
+use hall_of_fame::HallOfFame;
+use rand::Rng;
 use std::thread;
 use std::sync::mpsc;
-use std::sync::mpsc::{Sender, Receiver};
 use std::sync::Arc;
 use std::collections::HashMap;
-use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+// how many times a partner is picked before the hamming threshold is given up on
+const MAXIMUM_PARTNER_ATTEMPTS: u64 = 100;
+
 #[derive(Debug)]
-pub struct Simulation {
-    price_history: Arc<Vec<PriceData>>,
-    config: Arc<Config>,
-    bots: Vec<Bot>,
-    id: String
+pub struct Simulation<S: Strategy> {
+    market_history: Arc<MarketHistory>,
+    config: Arc<Config<S>>,
+    bots: Vec<Bot<S>>,
+    id: String,
+    progress: Progress,
+    hall_of_fame: HallOfFame<S>
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 enum Status {
     RUNNING,
@@ -30,36 +51,39 @@ enum Status {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct SimulationStatus {
-    status: Status
+    status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stop_reason: Option<StopReason> // set once the simulation has completed
 }
 
-pub fn breed(bots: &Vec::<Bot>, config: &Config) -> Vec::<Bot> {
-    // caculate total fitness
-    let total_fitness: f64 = bots.iter()
-        .map(|bot| bot.fitness)
-        .sum();
-
-    let mut breeding_pool: HashMap<u64, &Bot> = HashMap::new();
-
-    for bot in bots {
-        // calculate number of tickets
-        // always round up
-        // we may have over 100 breeding tickets -- that's okay :}
+// What is written to each generations file
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct Generation<S: Strategy> {
+    pub bots: Vec<Bot<S>>,
+    pub pareto_front: Vec<u64>, // ids of the bots no other bot beats on every objective
+    pub objectives: Vec<Objective>,
+    pub baseline: Option<Baseline>,
+    pub islands: Vec<IslandStats>,
+    pub species: Vec<Species>, // empty unless speciation is enabled
+    pub diversity: f64, // the average hamming distance between the bots
+    pub intervention: Option<Intervention> // what was done to the bots bred from this generation to bring back diversity
+}
 
-        let fitness = bot.fitness;
-        if fitness > 0.0 {
-            let percent_of_fitness = (fitness / total_fitness) * 100.0;
-            let number_of_tickets = percent_of_fitness.ceil() as u64;
+// the bots are expected to be sorted best first so the elite bots are the first ones
+// the generation is the one the new bots will be run in
+// partner fallbacks counts the children whose partner wasn't past the hamming threshold
+pub fn breed<S: Strategy>(bots: &[Bot<S>], config: &Config<S>, generation: u64, partner_fallbacks: &mut u64) -> Vec::<Bot<S>> {
+    breed_population(bots, config, generation, config.number_of_bots, partner_fallbacks)
+}
 
-            for _ in 0..number_of_tickets {
-                let position = breeding_pool.len() as u64;
-                breeding_pool.insert(position, &bot);
-            }
-        }
-    }
+// breeds a population of the given size, used for the whole population or for a single island
+pub fn breed_population<S: Strategy>(bots: &[Bot<S>], config: &Config<S>, generation: u64, number_of_bots: u64, partner_fallbacks: &mut u64) -> Vec::<Bot<S>> {
+    let mut parent_selection = ParentSelection::new(bots, config);
 
-    let mut new_bots = Vec::<Bot>::new();
+    let mut new_bots = Vec::<Bot<S>>::new();
     let mut rng = rand::thread_rng();
 
     // carry over elite bots
@@ -68,50 +92,89 @@ pub fn breed(bots: &Vec::<Bot>, config: &Config) -> Vec::<Bot> {
         new_bots.push(elite_bot);
     }
 
-    while new_bots.len() < config.number_of_bots as usize {
-        let index_one = rng.gen_range(0, breeding_pool.len() - 1) as u64;
+    // small species are bred from before they can be crowded out
+    if config.speciation.enabled {
+        speciation::protect_small_species(bots, config, generation, number_of_bots as usize, &mut new_bots, &mut rng);
+    }
 
-        let bot_one = breeding_pool.get(&index_one).unwrap();
-        loop {
-            let index_two = rng.gen_range(0, breeding_pool.len() - 1) as u64;
-            let bot_two = breeding_pool.get(&index_two).unwrap();
+    while new_bots.len() < number_of_bots as usize {
+        let bot_one = parent_selection.select(&mut rng);
+        let bot_two = match pick_partner(bot_one, &mut parent_selection, config, &mut rng) {
+            Ok(bot_two) => bot_two,
+            Err(bot_two) => {
+                *partner_fallbacks += 1;
+                bot_two
+            }
+        };
 
-            let hamming_value = bot_one.hamming(&bot_two);
+        // breed
+        let baby_bot = bot_one.breed(bot_two, &mut rng, config, new_bots.len() as u64, generation);
+        new_bots.push(baby_bot);
+    }
 
-            if bot_one.id != bot_two.id && hamming_value > config.hamming {
-                // breed
-                let baby_bot = bot_one.breed(&bot_two, &mut rng, config, new_bots.len() as u64);
-                new_bots.push(baby_bot);
-                break;
-            }
+    new_bots
+}
+
+// a partner has to be a different bot further then the hamming threshold away
+// when none turns up the most distant one that was picked is used so breeding never stalls
+// a bot that can only be picked with itself is bred with itself, a mutated copy
+// the fallback partner is returned as the error
+fn pick_partner<'a, S: Strategy, R: Rng>(bot_one: &Bot<S>, parent_selection: &mut ParentSelection<'a, S>, config: &Config<S>, rng: &mut R) -> Result<&'a Bot<S>, &'a Bot<S>> {
+    let mut most_distant: Option<(&Bot<S>, f64)> = None;
+    let mut last_picked = None;
+
+    for _ in 0..MAXIMUM_PARTNER_ATTEMPTS {
+        let bot_two = parent_selection.select(rng);
+        let hamming_value = bot_one.hamming(bot_two);
+
+        if bot_one.id != bot_two.id && hamming_value > config.hamming {
+            return Ok(bot_two);
         }
+
+        if bot_one.id != bot_two.id && most_distant.is_none_or(|(_, distance)| hamming_value > distance) {
+            most_distant = Some((bot_two, hamming_value));
+        }
+        last_picked = Some(bot_two);
     }
 
-    new_bots
+    match most_distant {
+        Some((bot_two, _)) => Err(bot_two),
+        None => Err(last_picked.unwrap())
+    }
 }
 
 // https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html
-impl Simulation {
-    pub fn web_create(path_to_price_history: &str, config: Config, id: String) -> Result<Simulation, Box<dyn Error>> {
-        let price_history_as_json = fs::read_to_string(path_to_price_history)?;
-
-        let mut price_history: Vec<PriceData> = serde_json::from_str(&price_history_as_json.as_str())?;
-        price_history.sort_by(|a, b| a.time.cmp(&b.time));
+impl<S: Strategy> Simulation<S> {
+    // loads a price series for each asset, the series are lined up on the times they all have data for
+    pub fn web_create(paths_to_price_histories: &[(Asset, &str)], config: Config<S>, id: String) -> Result<Simulation<S>, Box<dyn Error>> {
+        let mut price_histories = HashMap::<Asset, Vec<PriceData>>::new();
+        for (asset, path_to_price_history) in paths_to_price_histories {
+            let price_history_as_json = fs::read_to_string(path_to_price_history)?;
+            let price_history: Vec<PriceData> = serde_json::from_str(price_history_as_json.as_str())?;
+
+            price_histories.insert(*asset, price_history);
+        }
 
-        if config.validate_config().len() > 0 {
+        if !config.validate_config().is_empty() {
             panic!("Config validation failed!")
         }
 
-        let mut bots = Vec::<Bot>::new();
+        let mut bots = Vec::<Bot<S>>::new();
         for id in 0..config.number_of_bots {
             bots.push(Bot::new(&config, id));
         }
+        config.islands.assign(&mut bots);
+
+        let mut market_history = MarketHistory::new(price_histories, config.quote_asset);
+        market_history.set_baseline(config.benchmark_asset, config.starting_money);
 
         let simulation = Simulation {
-            price_history: Arc::new(price_history),
+            market_history: Arc::new(market_history),
             config: Arc::new(config),
             bots,
-            id
+            id,
+            progress: Progress::new(),
+            hall_of_fame: HallOfFame::new()
         };
 
         Ok(simulation)
@@ -133,56 +196,60 @@ impl Simulation {
         fs::create_dir_all(&path).unwrap();
 
         let mut simulation_status = SimulationStatus {
-            status: Status::RUNNING
+            status: Status::RUNNING,
+            stop_reason: None
         };
         self.update_status(&simulation_status);
 
+        self.progress = Progress::new();
         let run_result = self.run(1);
 
-        if run_result.is_err() {
-            simulation_status.status = Status::FAILED;
-            self.update_status(&simulation_status);
-            return;
+        match run_result {
+            Ok(stop_reason) => {
+                simulation_status.status = Status::COMPLETED;
+                simulation_status.stop_reason = Some(stop_reason);
+            },
+            Err(_) => simulation_status.status = Status::FAILED
         }
 
-        simulation_status.status = Status::COMPLETED;
         self.update_status(&simulation_status);
     }
 
 
-    pub fn run(&mut self, generation: u64) -> Result<(), Box<dyn Error>> {
+    // returns why the simulation stopped
+    pub fn run(&mut self, generation: u64) -> Result<StopReason, Box<dyn Error>> {
         if generation > self.config.number_of_generations {
-            return Ok(());
+            return Ok(StopReason::GenerationsCompleted);
         }
 
         println!("Generation {}", generation);
 
         // start the simulation
         // we will need to play around here to see what our options are for running the simulation
-        let (tx, rx): (Sender<Vec<Bot>>, Receiver<Vec<Bot>>) = mpsc::channel();
+        let (tx, rx) = mpsc::channel::<Vec<Bot<S>>>();
 
         let mut children = Vec::new();
 
         let number_of_bots_per_thread = self.config.number_of_bots / self.config.number_of_threads;
 
         for thread_number in 0..self.config.number_of_threads {
-            let mut bots = Vec::<Bot>::new();
+            let mut bots = Vec::<Bot<S>>::new();
             for _x in 0..number_of_bots_per_thread {
                 let bot = self.bots.pop().unwrap();
 
                 bots.push(bot);
             }
 
-            let price_history = Arc::clone(&self.price_history);
+            let market_history = Arc::clone(&self.market_history);
             let config = Arc::clone(&self.config);
             let tx_copy = mpsc::Sender::clone(&tx);
             let child = thread::spawn(move || {
                 // we start the simulation at the max number of averaging periods to give all bots a fair shot
                 // for x in config.traits.number_of_averaging_periods.max..price_history.len() as u64 {
-                for x in 0..price_history.len() as u64 {
+                for x in 0..market_history.len() as u64 {
                     bots
                         .iter_mut()
-                        .for_each(|bot| bot.run_period(&price_history, x, &config))
+                        .for_each(|bot| bot.run_period(&market_history, x, &config))
                 }
 
                 // calculate fitness (do simple calculation)
@@ -204,30 +271,83 @@ impl Simulation {
             children.push(child);
         }
 
-        let mut bots_post_simulation = Vec::<Bot>::new();
+        let mut bots_post_simulation = Vec::<Bot<S>>::new();
         for _ in 0..self.config.number_of_threads {
             let mut bots = rx.recv()?;
             bots_post_simulation.append(&mut bots);
         }
 
-        // sort the bots by fitness
-        bots_post_simulation.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
+        let species = speciation::speciate(&mut bots_post_simulation, &self.config.speciation);
+        nsga2::rank(&mut bots_post_simulation, &self.config.nsga2.objectives);
+
+        // sort the bots by fitness, or by front and crowding with NSGA-II
+        // disqualified bots go last either way
+        match self.config.nsga2.enabled {
+            true => bots_post_simulation.sort_by(nsga2::crowded_comparison),
+            false => bots_post_simulation.sort_by(|a, b| a.disqualified.cmp(&b.disqualified).then(b.fitness.total_cmp(&a.fitness)))
+        }
 
         for bot in &bots_post_simulation {
             println!("{:?}", bot.money);
         }
 
         // write the bots to a generations file
-        let results_as_json = serde_json::to_string_pretty(&bots_post_simulation)?;
+        let pareto_front = bots_post_simulation
+            .iter()
+            .filter(|bot| bot.pareto_rank == 0)
+            .map(|bot| bot.id)
+            .collect();
+        let island_stats = self.config.islands.stats(&bots_post_simulation);
+        let diversity = diversity::diversity(&bots_post_simulation);
+        let intervention = self.config.diversity.check(diversity, self.config.number_of_bots);
+        let results = Generation {
+            bots: bots_post_simulation,
+            pareto_front,
+            objectives: self.config.nsga2.objectives.clone(),
+            baseline: self.market_history.baseline().cloned(),
+            islands: island_stats,
+            species,
+            diversity,
+            intervention
+        };
+        let results_as_json = serde_json::to_string_pretty(&results)?;
 
         let file_name = format!("./simulations/{}/results/generation_{}.json", self.id, generation);
         fs::write(file_name, results_as_json)?;
 
-        let next_generation_bots = breed(&bots_post_simulation, &self.config);
-        self.bots = next_generation_bots;
-        self.run(generation + 1)?;
+        self.hall_of_fame.update(&results.bots, generation, &self.config.hall_of_fame);
+        let hall_of_fame_as_json = serde_json::to_string_pretty(&self.hall_of_fame)?;
+        fs::write(format!("./simulations/{}/hall_of_fame.json", self.id), hall_of_fame_as_json)?;
 
-        return Ok(())
+        // the last generation ends the run anyway
+        self.progress.update(&results.bots);
+        if generation < self.config.number_of_generations {
+            if let Some(stop_reason) = self.config.stopping_criteria.check(&self.progress, self.config.number_of_bots) {
+                println!("Stopping after generation {}: {:?}", generation, stop_reason);
+                return Ok(stop_reason);
+            }
+        }
+
+        // islands are only kept apart when breeding, their bots are run on the threads together
+        let mut partner_fallbacks = 0;
+        let mut next_generation_bots = islands::breed_islands(results.bots, &self.config, generation + 1, &mut partner_fallbacks);
+        if partner_fallbacks > 0 {
+            println!("{} bots of generation {} had no partner past the hamming threshold after {} attempts and were bred with the most distant bot picked", partner_fallbacks, generation + 1, MAXIMUM_PARTNER_ATTEMPTS);
+        }
+
+        if let Some(intervention) = &results.intervention {
+            let mut rng = rand::thread_rng();
+            let number_changed = diversity::intervene(&mut next_generation_bots, intervention, &self.config, generation + 1, &mut rng);
+            println!("Diversity of {:.3} is below the threshold, {:?} changed {} bots", intervention.diversity, intervention.action, number_changed);
+        }
+
+        let number_reinjected = self.hall_of_fame.reinject(&mut next_generation_bots, &self.config);
+        if number_reinjected > 0 {
+            println!("Put {} bots from the hall of fame back into generation {}", number_reinjected, generation + 1);
+        }
+
+        self.bots = next_generation_bots;
+        self.run(generation + 1)
     }
 
     pub fn state(&self) {
@@ -0,0 +1,181 @@
+use std::cmp::Ordering;
+use crate::bot::Bot;
+use crate::config::ConfigError;
+use crate::strategy::Strategy;
+
+// What the bots are ranked on when there is more then one goal
+// MaxDrawdown is minimised, everything else is maximised
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Objective {
+    Return,
+    MaxDrawdown,
+    TradeCount,
+    Fitness
+}
+
+impl Objective {
+    // signed so that a bigger value is always better
+    fn value<S: Strategy>(&self, bot: &Bot<S>) -> f64 {
+        match self {
+            Objective::Return => bot.metrics.total_return,
+            Objective::MaxDrawdown => -bot.metrics.max_drawdown,
+            Objective::TradeCount => bot.metrics.number_of_trades as f64,
+            Objective::Fitness => bot.fitness
+        }
+    }
+}
+
+fn default_objectives() -> Vec<Objective> {
+    vec!(Objective::Return, Objective::MaxDrawdown)
+}
+
+// Ranks the bots by non-dominated sorting and crowding distance instead of by fitness alone
+// The pareto front of the objectives is recorded in every generation file either way
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Nsga2 {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_objectives")]
+    pub objectives: Vec<Objective>
+}
+
+impl Default for Nsga2 {
+    fn default() -> Nsga2 {
+        Nsga2 {
+            enabled: false,
+            objectives: default_objectives()
+        }
+    }
+}
+
+impl Nsga2 {
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.objectives.is_empty() {
+            config_errors.push(ConfigError::new("NSGA-II needs at least one objective".to_string(), "Nsga2.Objectives".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+// one set of values dominates another when it's no worse on every objective and better on at least one
+pub fn dominates(values_one: &[f64], values_two: &[f64]) -> bool {
+    let no_worse = values_one.iter().zip(values_two).all(|(one, two)| one >= two);
+    let better = values_one.iter().zip(values_two).any(|(one, two)| one > two);
+
+    no_worse && better
+}
+
+// splits the indexes of the values into fronts, the first front is dominated by nothing
+pub fn non_dominated_sort(values: &[Vec<f64>]) -> Vec<Vec<usize>> {
+    let mut dominated_by_count = vec!(0; values.len());
+    let mut dominates_indexes = vec!(Vec::<usize>::new(); values.len());
+
+    for one in 0..values.len() {
+        for two in 0..values.len() {
+            if dominates(&values[one], &values[two]) {
+                dominates_indexes[one].push(two);
+            } else if dominates(&values[two], &values[one]) {
+                dominated_by_count[one] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::<Vec<usize>>::new();
+    let mut front: Vec<usize> = (0..values.len())
+        .filter(|index| dominated_by_count[*index] == 0)
+        .collect();
+
+    while !front.is_empty() {
+        let mut next_front = Vec::<usize>::new();
+
+        for index in &front {
+            for dominated_index in &dominates_indexes[*index] {
+                dominated_by_count[*dominated_index] -= 1;
+                if dominated_by_count[*dominated_index] == 0 {
+                    next_front.push(*dominated_index);
+                }
+            }
+        }
+
+        fronts.push(front);
+        front = next_front;
+    }
+
+    fronts
+}
+
+fn values_for_objective(values: &[Vec<f64>], front: &[usize], objective: usize) -> Vec<f64> {
+    front.iter().map(|index| values[*index][objective]).collect()
+}
+
+// the distance is in the same order as the front
+// the bots at the edges of an objective are always kept so they are given the largest distance
+// f64::MAX is used over infinity so it can be written to json
+pub fn crowding_distance(values: &[Vec<f64>], front: &[usize]) -> Vec<f64> {
+    let mut distances = vec!(0.0; front.len());
+    if front.len() <= 2 {
+        return vec!(f64::MAX; front.len());
+    }
+
+    let number_of_objectives = values[front[0]].len();
+    for objective in 0..number_of_objectives {
+        let objective_values = values_for_objective(values, front, objective);
+
+        let mut positions: Vec<usize> = (0..front.len()).collect();
+        positions.sort_by(|a, b| objective_values[*a].total_cmp(&objective_values[*b]));
+
+        let first = *positions.first().unwrap();
+        let last = *positions.last().unwrap();
+        distances[first] = f64::MAX;
+        distances[last] = f64::MAX;
+
+        let range = objective_values[last] - objective_values[first];
+        if range <= 0.0 {
+            continue;
+        }
+
+        for window in positions.windows(3) {
+            let gap = objective_values[window[2]] - objective_values[window[0]];
+            distances[window[1]] = (distances[window[1]] + (gap / range)).min(f64::MAX);
+        }
+    }
+
+    distances
+}
+
+// sets the pareto rank and crowding distance of every bot
+// disqualified bots are left out of the sort and put in a front of their own after the rest
+pub fn rank<S: Strategy>(bots: &mut [Bot<S>], objectives: &[Objective]) {
+    let qualified_indexes: Vec<usize> = (0..bots.len())
+        .filter(|index| !bots[*index].disqualified)
+        .collect();
+    let values: Vec<Vec<f64>> = qualified_indexes
+        .iter()
+        .map(|index| objectives.iter().map(|objective| objective.value(&bots[*index])).collect())
+        .collect();
+
+    let fronts = non_dominated_sort(&values);
+    for (pareto_rank, front) in fronts.iter().enumerate() {
+        for (position, distance) in front.iter().zip(crowding_distance(&values, front)) {
+            let bot = &mut bots[qualified_indexes[*position]];
+            bot.pareto_rank = pareto_rank as u64;
+            bot.crowding_distance = distance;
+        }
+    }
+
+    for bot in bots.iter_mut().filter(|bot| bot.disqualified) {
+        bot.pareto_rank = fronts.len() as u64;
+        bot.crowding_distance = 0.0;
+    }
+}
+
+// lower fronts come first and within a front the less crowded bots do
+pub fn crowded_comparison<S: Strategy>(bot_one: &Bot<S>, bot_two: &Bot<S>) -> Ordering {
+    bot_one.pareto_rank
+        .cmp(&bot_two.pareto_rank)
+        .then(bot_two.crowding_distance.total_cmp(&bot_one.crowding_distance))
+}
@@ -0,0 +1,227 @@
+use std::cmp::Ordering;
+use rand::Rng;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use crate::bot::Bot;
+use crate::config::{Config, ConfigError};
+use crate::strategy::Strategy;
+use super::nsga2;
+
+// Roulette gives each bot tickets by its share of the positive fitness (the original behaviour)
+// Tournament takes the best of TournamentSize random bots, LinearRank weights bots by their place in the ranking,
+// StochasticUniversalSampling spreads evenly spaced picks over the fitness and Truncation only breeds from the top of the ranking
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum SelectionMethod {
+    #[default]
+    Roulette,
+    Tournament,
+    LinearRank,
+    StochasticUniversalSampling,
+    Truncation
+}
+
+fn default_tournament_size() -> u64 {
+    2
+}
+
+fn default_selection_pressure() -> f64 {
+    1.5
+}
+
+fn default_truncation_percentage() -> f64 {
+    50.0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Selection {
+    #[serde(default)]
+    pub method: SelectionMethod,
+    #[serde(default = "default_tournament_size")]
+    pub tournament_size: u64,
+    #[serde(default = "default_selection_pressure")]
+    pub selection_pressure: f64, // how many times more likely the best bot is picked then the average one, from 1 to 2
+    #[serde(default = "default_truncation_percentage")]
+    pub truncation_percentage: f64 // the percentage of the ranking that is bred from
+}
+
+impl Default for Selection {
+    fn default() -> Selection {
+        Selection {
+            method: SelectionMethod::Roulette,
+            tournament_size: default_tournament_size(),
+            selection_pressure: default_selection_pressure(),
+            truncation_percentage: default_truncation_percentage()
+        }
+    }
+}
+
+impl Selection {
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.tournament_size < 1 {
+            config_errors.push(ConfigError::new("Tournament Size must be at least 1".to_string(), "Selection.TournamentSize".to_string()));
+        }
+
+        if self.selection_pressure < 1.0 || self.selection_pressure > 2.0 {
+            config_errors.push(ConfigError::new("Selection Pressure must be between 1 and 2".to_string(), "Selection.SelectionPressure".to_string()));
+        }
+
+        if self.truncation_percentage <= 0.0 || self.truncation_percentage > 100.0 {
+            config_errors.push(ConfigError::new("Truncation Percentage must be greater then 0 and at most 100".to_string(), "Selection.TruncationPercentage".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+// best first, by fitness (shared with speciation) or by front and crowding with NSGA-II
+fn compare<S: Strategy>(bot_one: &Bot<S>, bot_two: &Bot<S>, config: &Config<S>) -> Ordering {
+    match config.nsga2.enabled {
+        true => nsga2::crowded_comparison(bot_one, bot_two),
+        false => bot_two.selection_fitness().total_cmp(&bot_one.selection_fitness())
+    }
+}
+
+// gives each bot tickets by its share of the total positive fitness
+fn roulette_pool<'a, S: Strategy>(bots: &[&'a Bot<S>]) -> Vec<&'a Bot<S>> {
+    // caculate total fitness
+    // only positive fitness earns tickets so it's the only fitness counted
+    let total_fitness: f64 = bots.iter()
+        .map(|bot| bot.selection_fitness().max(0.0))
+        .sum();
+
+    let mut breeding_pool = Vec::<&Bot<S>>::new();
+
+    for bot in bots {
+        // calculate number of tickets
+        // always round up
+        // we may have over 100 breeding tickets -- that's okay :}
+
+        let fitness = bot.selection_fitness();
+        if fitness > 0.0 {
+            let percent_of_fitness = (fitness / total_fitness) * 100.0;
+            let number_of_tickets = percent_of_fitness.ceil() as u64;
+
+            for _ in 0..number_of_tickets {
+                breeding_pool.push(*bot);
+            }
+        }
+    }
+
+    // when no bot has a positive fitness they all get the same chance
+    if breeding_pool.is_empty() {
+        return bots.to_vec();
+    }
+
+    breeding_pool
+}
+
+// the best bot gets the selection pressure as its weight and the worst gets 2 - selection pressure
+fn linear_rank_weights(number_of_bots: usize, selection_pressure: f64) -> Vec<f64> {
+    if number_of_bots == 1 {
+        return vec!(1.0);
+    }
+
+    (0..number_of_bots)
+        .map(|position| {
+            let rank = (number_of_bots - 1 - position) as f64 / (number_of_bots - 1) as f64;
+            (2.0 - selection_pressure) + (2.0 * (selection_pressure - 1.0) * rank)
+        })
+        .collect()
+}
+
+// evenly spaced pointers over the fitness so a bot is picked close to its share every time
+// fitness is shifted up by the worst fitness so negative fitness still gets a share
+pub fn stochastic_universal_sampling<'a, S: Strategy, R: Rng>(bots: &[&'a Bot<S>], number_of_picks: usize, rng: &mut R) -> Vec<&'a Bot<S>> {
+    let worst_fitness = bots.iter().map(|bot| bot.selection_fitness()).fold(f64::INFINITY, f64::min);
+    let mut weights: Vec<f64> = bots.iter().map(|bot| bot.selection_fitness() - worst_fitness).collect();
+    if weights.iter().all(|weight| *weight <= 0.0) {
+        weights = vec!(1.0; bots.len());
+    }
+
+    let distance = weights.iter().sum::<f64>() / number_of_picks as f64;
+    let mut pointer = rng.gen_range(0.0, distance);
+
+    let mut picks = Vec::<&Bot<S>>::new();
+    let mut cumulative_weight = 0.0;
+    for (bot, weight) in bots.iter().zip(weights) {
+        cumulative_weight += weight;
+        while pointer < cumulative_weight && picks.len() < number_of_picks {
+            picks.push(*bot);
+            pointer += distance;
+        }
+    }
+
+    // float error can leave the last pointer just past the end
+    while picks.len() < number_of_picks {
+        picks.push(*bots.last().unwrap());
+    }
+
+    picks.shuffle(rng);
+    picks
+}
+
+// How parents are picked for breeding
+// NSGA-II ranks bots by front so the fitness proportional methods use a binary tournament on the ranking instead
+pub enum ParentSelection<'a, S: Strategy> {
+    Roulette(Vec<&'a Bot<S>>),
+    Tournament(Vec<&'a Bot<S>>, usize), // the bots in ranking order and the size of the tournament
+    LinearRank(Vec<&'a Bot<S>>, WeightedIndex<f64>),
+    StochasticUniversalSampling(Vec<&'a Bot<S>>, Vec<&'a Bot<S>>), // the bots and the picks not used yet
+    Truncation(Vec<&'a Bot<S>>)
+}
+
+impl<'a, S: Strategy> ParentSelection<'a, S> {
+    pub fn new(bots: &'a [Bot<S>], config: &Config<S>) -> ParentSelection<'a, S> {
+        // disqualified bots are never bred from unless there is nothing else
+        let mut eligible_bots: Vec<&Bot<S>> = bots.iter().filter(|bot| !bot.disqualified).collect();
+        if eligible_bots.is_empty() {
+            eligible_bots = bots.iter().collect();
+        }
+        eligible_bots.sort_by(|bot_one, bot_two| compare(bot_one, bot_two, config));
+
+        let selection = &config.selection;
+        match (selection.method, config.nsga2.enabled) {
+            (SelectionMethod::Roulette, false) => ParentSelection::Roulette(roulette_pool(&eligible_bots)),
+            (SelectionMethod::StochasticUniversalSampling, false) => ParentSelection::StochasticUniversalSampling(eligible_bots, Vec::new()),
+            (SelectionMethod::Roulette, true) | (SelectionMethod::StochasticUniversalSampling, true) => ParentSelection::Tournament(eligible_bots, 2),
+            (SelectionMethod::Tournament, _) => ParentSelection::Tournament(eligible_bots, selection.tournament_size as usize),
+            (SelectionMethod::LinearRank, _) => {
+                let weights = linear_rank_weights(eligible_bots.len(), selection.selection_pressure);
+                ParentSelection::LinearRank(eligible_bots, WeightedIndex::new(weights).unwrap())
+            },
+            (SelectionMethod::Truncation, _) => {
+                let number_kept = ((eligible_bots.len() as f64 * (selection.truncation_percentage / 100.0)).ceil() as usize).max(1);
+                eligible_bots.truncate(number_kept);
+                ParentSelection::Truncation(eligible_bots)
+            }
+        }
+    }
+
+    pub fn select<R: Rng>(&mut self, rng: &mut R) -> &'a Bot<S> {
+        match self {
+            ParentSelection::Roulette(breeding_pool) => breeding_pool[rng.gen_range(0, breeding_pool.len())],
+            // the bots are in ranking order so the lowest position drawn wins
+            ParentSelection::Tournament(bots, tournament_size) => {
+                let best_position = (0..*tournament_size)
+                    .map(|_| rng.gen_range(0, bots.len()))
+                    .min()
+                    .unwrap();
+
+                bots[best_position]
+            },
+            ParentSelection::LinearRank(bots, weighted_index) => bots[weighted_index.sample(rng)],
+            // picks are made a generation's worth at a time
+            ParentSelection::StochasticUniversalSampling(bots, picks) => {
+                if picks.is_empty() {
+                    *picks = stochastic_universal_sampling(bots, bots.len() * 2, rng);
+                }
+
+                picks.pop().unwrap()
+            },
+            ParentSelection::Truncation(bots) => bots[rng.gen_range(0, bots.len())]
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use rand::Rng;
+use crate::bot::Bot;
+use crate::config::{Config, ConfigError};
+use crate::strategy::Strategy;
+
+fn default_threshold() -> f64 {
+    10.0
+}
+
+fn default_protected_species_size() -> u64 {
+    3
+}
+
+// Groups the bots into species by their hamming distance so different kinds of bots aren't bred out of the population
+// A bot joins the first species whose best bot is within the threshold of it, the best bots start the species
+// With fitness sharing the fitness a bot is picked for breeding on is divided by the size of its species
+// and species smaller then the protected size always get a child bred from their best bots
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Speciation {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_threshold")]
+    pub threshold: f64, // bots closer then this are the same species, on the same scale as Hamming
+    #[serde(default = "default_protected_species_size")]
+    pub protected_species_size: u64
+}
+
+impl Default for Speciation {
+    fn default() -> Speciation {
+        Speciation {
+            enabled: false,
+            threshold: default_threshold(),
+            protected_species_size: default_protected_species_size()
+        }
+    }
+}
+
+impl Speciation {
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.threshold < 0.0 || self.threshold > 100.0 {
+            config_errors.push(ConfigError::new("Threshold must be between 0 and 100".to_string(), "Speciation.Threshold".to_string()));
+        }
+
+        config_errors
+    }
+}
+
+// A species in a generation
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Species {
+    pub species: u64,
+    pub representative: u64, // the id of the best bot in the species, every other bot is within the threshold of it
+    pub number_of_bots: u64,
+    pub best_fitness: Option<f64>, // of the bots that weren't disqualified, none if every bot was
+    pub average_fitness: Option<f64>
+}
+
+// the niche count is the size of the species, losing bots are pushed further down instead of being lifted towards 0
+fn shared_fitness(fitness: f64, niche_count: u64) -> f64 {
+    match fitness >= 0.0 {
+        true => fitness / niche_count as f64,
+        false => fitness * niche_count as f64
+    }
+}
+
+// puts every bot in a species and shares out their fitness
+pub fn speciate<S: Strategy>(bots: &mut [Bot<S>], speciation: &Speciation) -> Vec<Species> {
+    if !speciation.enabled {
+        return Vec::new();
+    }
+
+    // the best bots are looked at first so they represent their species, disqualified bots only join or start a species after the rest
+    let mut order: Vec<usize> = (0..bots.len()).collect();
+    order.sort_by(|a, b| bots[*a].disqualified.cmp(&bots[*b].disqualified).then(bots[*b].fitness.total_cmp(&bots[*a].fitness)));
+
+    let mut representatives = Vec::<usize>::new();
+    let mut members = Vec::<Vec<usize>>::new();
+    for position in order {
+        let species = representatives
+            .iter()
+            .position(|representative| bots[*representative].hamming(&bots[position]) < speciation.threshold);
+
+        match species {
+            Some(species) => members[species].push(position),
+            None => {
+                representatives.push(position);
+                members.push(vec!(position));
+            }
+        }
+    }
+
+    let mut all_species = Vec::<Species>::new();
+    for (species, (representative, species_members)) in representatives.iter().zip(&members).enumerate() {
+        // disqualified bots aren't counted in the niche as they are never bred from
+        let qualified_fitness: Vec<f64> = species_members
+            .iter()
+            .filter(|position| !bots[**position].disqualified)
+            .map(|position| bots[*position].fitness)
+            .collect();
+        let niche_count = (qualified_fitness.len() as u64).max(1);
+
+        for position in species_members {
+            let bot = &mut bots[*position];
+            bot.species = species as u64;
+            bot.shared_fitness = Some(shared_fitness(bot.fitness, niche_count));
+        }
+
+        all_species.push(Species {
+            species: species as u64,
+            representative: bots[*representative].id,
+            number_of_bots: species_members.len() as u64,
+            best_fitness: qualified_fitness.first().copied(),
+            average_fitness: match qualified_fitness.is_empty() {
+                true => None,
+                false => Some(qualified_fitness.iter().sum::<f64>() / qualified_fitness.len() as f64)
+            }
+        });
+    }
+
+    all_species
+}
+
+// small species get a child from their best two bots, or a mutated copy of a species of one, before anything else is bred
+// the bots are expected to be sorted best first so the first bot seen of a species is its best
+pub fn protect_small_species<S: Strategy, R: Rng>(bots: &[Bot<S>], config: &Config<S>, generation: u64, number_of_bots: usize, new_bots: &mut Vec<Bot<S>>, rng: &mut R) {
+    let mut species_seen = Vec::<u64>::new();
+    for bot in bots.iter().filter(|bot| !bot.disqualified) {
+        if new_bots.len() >= number_of_bots {
+            return;
+        }
+
+        if species_seen.contains(&bot.species) {
+            continue;
+        }
+        species_seen.push(bot.species);
+
+        let species_members: Vec<&Bot<S>> = bots.iter().filter(|member| member.species == bot.species && !member.disqualified).collect();
+        if species_members.len() as u64 >= config.speciation.protected_species_size {
+            continue;
+        }
+
+        let partner = species_members.get(1).copied().unwrap_or(bot);
+        let baby_bot = bot.breed(partner, rng, config, new_bots.len() as u64, generation);
+        new_bots.push(baby_bot);
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+use crate::bot::Bot;
+use crate::config::ConfigError;
+use crate::strategy::Strategy;
+
+// Why a simulation stopped
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum StopReason {
+    GenerationsCompleted,
+    NoImprovement,
+    TargetFitness,
+    TimeBudget,
+    EvaluationBudget
+}
+
+// Ends a simulation before the number of generations once it has stopped getting anywhere or used up its budget
+// They are checked after every generation has been run, every criteria is off unless it's set
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct StoppingCriteria {
+    #[serde(default)]
+    pub no_improvement_generations: Option<u64>, // generations in a row without a better best or mean fitness
+    #[serde(default)]
+    pub target_fitness: Option<f64>,
+    #[serde(default)]
+    pub time_budget_seconds: Option<u64>,
+    #[serde(default)]
+    pub evaluation_budget: Option<u64> // bot runs, a generation that would go over it isn't started
+}
+
+// What the simulation has done so far
+#[derive(Debug)]
+pub struct Progress {
+    pub started: Instant,
+    pub evaluations: u64,
+    pub best_fitness: Option<f64>,
+    pub mean_fitness: Option<f64>,
+    pub generations_without_improvement: u64
+}
+
+impl Progress {
+    pub fn new() -> Progress {
+        Progress {
+            started: Instant::now(),
+            evaluations: 0,
+            best_fitness: None,
+            mean_fitness: None,
+            generations_without_improvement: 0
+        }
+    }
+
+    // adds a generation that has been run, only bots that weren't disqualified count towards the fitness
+    pub fn update<S: Strategy>(&mut self, bots: &[Bot<S>]) {
+        self.evaluations += bots.len() as u64;
+
+        let qualified_fitness: Vec<f64> = bots.iter().filter(|bot| !bot.disqualified).map(|bot| bot.fitness).collect();
+        if qualified_fitness.is_empty() {
+            self.generations_without_improvement += 1;
+            return;
+        }
+
+        let best_fitness = qualified_fitness.iter().copied().fold(f64::MIN, f64::max);
+        let mean_fitness = qualified_fitness.iter().sum::<f64>() / qualified_fitness.len() as f64;
+
+        let best_improved = self.best_fitness.is_none_or(|previous_best| best_fitness > previous_best);
+        let mean_improved = self.mean_fitness.is_none_or(|previous_mean| mean_fitness > previous_mean);
+        match best_improved || mean_improved {
+            true => self.generations_without_improvement = 0,
+            false => self.generations_without_improvement += 1
+        }
+
+        self.best_fitness = Some(self.best_fitness.map_or(best_fitness, |previous_best| previous_best.max(best_fitness)));
+        self.mean_fitness = Some(self.mean_fitness.map_or(mean_fitness, |previous_mean| previous_mean.max(mean_fitness)));
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Progress {
+        Progress::new()
+    }
+}
+
+impl StoppingCriteria {
+    // the reason to stop after the last generation, the number of bots is the size of the next generation
+    pub fn check(&self, progress: &Progress, number_of_bots: u64) -> Option<StopReason> {
+        if self.target_fitness.is_some_and(|target_fitness| progress.best_fitness.is_some_and(|best_fitness| best_fitness >= target_fitness)) {
+            return Some(StopReason::TargetFitness);
+        }
+
+        if self.no_improvement_generations.is_some_and(|generations| progress.generations_without_improvement >= generations) {
+            return Some(StopReason::NoImprovement);
+        }
+
+        if self.evaluation_budget.is_some_and(|evaluation_budget| progress.evaluations + number_of_bots > evaluation_budget) {
+            return Some(StopReason::EvaluationBudget);
+        }
+
+        if self.time_budget_seconds.is_some_and(|time_budget_seconds| progress.started.elapsed() >= Duration::from_secs(time_budget_seconds)) {
+            return Some(StopReason::TimeBudget);
+        }
+
+        None
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.no_improvement_generations == Some(0) {
+            config_errors.push(ConfigError::new("No Improvement Generations must be at least 1".to_string(), "StoppingCriteria.NoImprovementGenerations".to_string()));
+        }
+
+        if self.time_budget_seconds == Some(0) {
+            config_errors.push(ConfigError::new("Time Budget Seconds must be at least 1".to_string(), "StoppingCriteria.TimeBudgetSeconds".to_string()));
+        }
+
+        if self.evaluation_budget == Some(0) {
+            config_errors.push(ConfigError::new("Evaluation Budget must be at least 1".to_string(), "StoppingCriteria.EvaluationBudget".to_string()));
+        }
+
+        config_errors
+    }
+}
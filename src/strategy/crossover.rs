@@ -0,0 +1,115 @@
+use rand::Rng;
+use crate::config::ConfigError;
+
+// How the number genes of two parents are combined
+// Uniform copies each gene from one parent or the other (the original behaviour)
+// BlxAlpha draws from the range between the parents stretched by Alpha of the gap on each side,
+// Sbx (simulated binary crossover) spreads children around the parents with the DistributionIndex controlling how close
+// and Arithmetic takes a weighted average of the parents
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum CrossoverOperator {
+    #[default]
+    Uniform,
+    BlxAlpha,
+    Sbx,
+    Arithmetic
+}
+
+fn default_alpha() -> f64 {
+    0.5
+}
+
+fn default_distribution_index() -> f64 {
+    15.0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Crossover {
+    #[serde(default)]
+    pub operator: CrossoverOperator,
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    #[serde(default = "default_distribution_index")]
+    pub distribution_index: f64, // bigger keeps children closer to their parents
+    #[serde(default)]
+    pub blend_weight: Option<f64> // weight of the first parent, random for every gene when not set
+}
+
+impl Default for Crossover {
+    fn default() -> Crossover {
+        Crossover {
+            operator: CrossoverOperator::Uniform,
+            alpha: default_alpha(),
+            distribution_index: default_distribution_index(),
+            blend_weight: None
+        }
+    }
+}
+
+pub fn pick<T, R: Rng>(value_one: T, value_two: T, rng: &mut R) -> T {
+    match rng.gen_bool(0.5) {
+        true => value_one,
+        false => value_two
+    }
+}
+
+impl Crossover {
+    // children can land outside the parents so they are clamped to the bounds of the gene
+    pub fn cross_f64<R: Rng>(&self, value_one: f64, value_two: f64, min: f64, max: f64, rng: &mut R) -> f64 {
+        let value = match self.operator {
+            CrossoverOperator::Uniform => return pick(value_one, value_two, rng),
+            CrossoverOperator::BlxAlpha => {
+                let gap = (value_one - value_two).abs();
+                if gap == 0.0 {
+                    return value_one;
+                }
+
+                let low = value_one.min(value_two) - (self.alpha * gap);
+                let high = value_one.max(value_two) + (self.alpha * gap);
+                rng.gen_range(low, high)
+            },
+            CrossoverOperator::Sbx => {
+                let random: f64 = rng.gen();
+                let exponent = 1.0 / (self.distribution_index + 1.0);
+                let spread = match random <= 0.5 {
+                    true => (2.0 * random).powf(exponent),
+                    false => (1.0 / (2.0 * (1.0 - random))).powf(exponent)
+                };
+
+                // each pair of parents makes two children, one of them is kept
+                let (value_one, value_two) = pick((value_one, value_two), (value_two, value_one), rng);
+                0.5 * (((1.0 + spread) * value_one) + ((1.0 - spread) * value_two))
+            },
+            CrossoverOperator::Arithmetic => {
+                let weight = self.blend_weight.unwrap_or_else(|| rng.gen());
+                (weight * value_one) + ((1.0 - weight) * value_two)
+            }
+        };
+
+        value.max(min).min(max)
+    }
+
+    // whole number genes are crossed as numbers and rounded back to the nearest whole number
+    pub fn cross_u64<R: Rng>(&self, value_one: u64, value_two: u64, min: u64, max: u64, rng: &mut R) -> u64 {
+        self.cross_f64(value_one as f64, value_two as f64, min as f64, max as f64, rng).round() as u64
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.alpha < 0.0 {
+            config_errors.push(ConfigError::new("Alpha can not be negative".to_string(), "Crossover.Alpha".to_string()));
+        }
+
+        if self.distribution_index < 0.0 {
+            config_errors.push(ConfigError::new("Distribution Index can not be negative".to_string(), "Crossover.DistributionIndex".to_string()));
+        }
+
+        if self.blend_weight.is_some_and(|blend_weight| !(0.0..=1.0).contains(&blend_weight)) {
+            config_errors.push(ConfigError::new("Blend Weight must be between 0 and 1".to_string(), "Crossover.BlendWeight".to_string()));
+        }
+
+        config_errors
+    }
+}
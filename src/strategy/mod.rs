@@ -0,0 +1,74 @@
+pub mod momentum;
+pub mod crossover;
+pub mod mutation;
+pub use momentum::Momentum;
+use std::fmt::Debug;
+use rand::Rng;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use crate::config::{Config, ConfigError};
+use crate::price_data::MarketHistory;
+use crate::asset::Asset;
+use crate::bot::holdings::Side;
+use crate::bot::orders::OrderType;
+
+// Everything a strategy is allowed to look at when deciding what to do on a period
+pub struct PeriodContext<'a> {
+    pub period: u64,
+    pub market_history: &'a MarketHistory
+}
+
+// Returned by a strategy when it wants to open a new position
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EntrySignal {
+    pub asset: Asset,
+    pub percent_purchase: f64,
+    pub side: Side,
+    pub order_type: OrderType,
+    pub entry_offset_percentage: f64,
+    pub order_expiry_periods: u64,
+    pub leverage: f64
+}
+
+// The rules a holding is managed by once it has been opened
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitRules {
+    pub stop_loss: f64,
+    pub trailing_stop_loss: f64,
+    pub target_sell_percentage: f64,
+    pub minimum_holding_periods: u64,
+    pub maximum_holding_periods: u64
+}
+
+// A strategy family the genetic algorithm can evolve
+// The genome is what gets bred and mutated, the genome config holds the bounds of each gene
+pub trait Strategy: Debug + Send + Sync + Sized + 'static {
+    type Genome: Copy + PartialEq + Debug + Serialize + DeserializeOwned + Send + Sync + 'static;
+    type GenomeConfig: Debug + Serialize + DeserializeOwned + Send + Sync + 'static;
+
+    fn random_genome<R: Rng>(rng: &mut R, config: &Config<Self>) -> Self::Genome;
+
+    // number of periods the bot has to wait before it has enough history to make decisions
+    fn warmup_periods(genome: &Self::Genome) -> u64;
+
+    fn decide(genome: &Self::Genome, context: &PeriodContext) -> Option<EntrySignal>;
+
+    fn exit_rules(genome: &Self::Genome) -> ExitRules;
+
+    // generations start at 1, later generations can mutate less as mutation anneals
+    fn mutate<R: Rng>(genome: &mut Self::Genome, rng: &mut R, config: &Config<Self>, generation: u64);
+
+    // mutates every gene with the given chance instead of the annealed mutation chance, the step sizes still anneal
+    fn mutate_with_chance<R: Rng>(genome: &mut Self::Genome, rng: &mut R, config: &Config<Self>, generation: u64, mutation_chance: f64);
+
+    fn crossover<R: Rng>(genome_one: &Self::Genome, genome_two: &Self::Genome, rng: &mut R, config: &Config<Self>) -> Self::Genome;
+
+    // percent difference between two genomes, used for the hamming check when breeding
+    fn distance(genome_one: &Self::Genome, genome_two: &Self::Genome) -> f64;
+
+    fn validate_config(config: &Self::GenomeConfig) -> Vec<ConfigError>;
+
+    // the assets bots of the strategy are allowed to trade
+    fn tradeable_assets(config: &Self::GenomeConfig) -> Vec<Asset>;
+}
@@ -0,0 +1,159 @@
+use rand::Rng;
+use crate::bot::traits::{Traits, Direction, NUMBER_OF_STEP_SIZES};
+use crate::bot::holdings::Side;
+use crate::asset::Asset;
+use crate::config::{Config, ConfigError};
+use crate::config::traits::Traits as TraitsConfig;
+use super::{Strategy, PeriodContext, EntrySignal, ExitRules};
+use super::crossover::pick;
+
+// the genes the distance was first averaged over
+const NUMBER_OF_ORIGINAL_GENES: f64 = 9.0;
+
+// Buys when the price has moved up by a given amount over the averaging periods,
+// shorts when it has moved down by the same amount (depending on the direction gene)
+// and exits on a stop loss, trailing stop loss, targeted sell price or holding period
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Momentum;
+
+fn calculate_momentum(current_price: f64, previous_price: f64) -> f64 {
+    ((current_price - previous_price) / previous_price) * 100.0
+}
+
+fn calculate_percent_difference(value_one: f64, value_two: f64) -> f64 {
+    // genes fixed at zero would otherwise divide by zero
+    if value_one == value_two {
+        return 0.0;
+    }
+
+    let diff = (value_one - value_two) / ((value_one + value_two) / 2.0);
+
+    diff.abs() * 100.0
+}
+
+fn average_step_sizes(step_sizes_one: &[f64; NUMBER_OF_STEP_SIZES], step_sizes_two: &[f64; NUMBER_OF_STEP_SIZES]) -> [f64; NUMBER_OF_STEP_SIZES] {
+    let mut step_sizes = [0.0; NUMBER_OF_STEP_SIZES];
+    for (step_size, (step_size_one, step_size_two)) in step_sizes.iter_mut().zip(step_sizes_one.iter().zip(step_sizes_two)) {
+        *step_size = (step_size_one + step_size_two) / 2.0;
+    }
+
+    step_sizes
+}
+
+impl Strategy for Momentum {
+    type Genome = Traits;
+    type GenomeConfig = TraitsConfig;
+
+    fn random_genome<R: Rng>(rng: &mut R, config: &Config<Self>) -> Traits {
+        Traits::new(rng, config)
+    }
+
+    fn warmup_periods(genome: &Traits) -> u64 {
+        genome.number_of_averaging_periods
+    }
+
+    fn decide(genome: &Traits, context: &PeriodContext) -> Option<EntrySignal> {
+        let price_history = context.market_history.price_history(genome.asset)?;
+        let current_price_data = price_history.get(context.period as usize)?;
+        let old_price_data = price_history.get((context.period - genome.number_of_averaging_periods) as usize)?;
+        let momentum = calculate_momentum(current_price_data.open, old_price_data.open);
+        let in_range = |momentum: f64| momentum >= genome.minimum_buy_momentum && momentum <= genome.maximum_buy_momentum;
+
+        let can_go_long = genome.direction == Direction::Up || genome.direction == Direction::Both;
+        let can_go_short = genome.direction == Direction::Down || genome.direction == Direction::Both;
+
+        let side = if can_go_long && in_range(momentum) {
+            Side::Long
+        } else if can_go_short && in_range(-momentum) {
+            Side::Short
+        } else {
+            return None;
+        };
+
+        Some(EntrySignal {
+            asset: genome.asset,
+            percent_purchase: genome.percent_purchase,
+            side,
+            order_type: genome.order_type,
+            entry_offset_percentage: genome.entry_offset_percentage,
+            order_expiry_periods: genome.order_expiry_periods,
+            leverage: genome.leverage
+        })
+    }
+
+    fn exit_rules(genome: &Traits) -> ExitRules {
+        ExitRules {
+            stop_loss: genome.stop_loss,
+            trailing_stop_loss: genome.trailing_stop_loss,
+            target_sell_percentage: genome.target_sell_percentage,
+            minimum_holding_periods: genome.minimum_holding_periods,
+            maximum_holding_periods: genome.maximum_holding_periods
+        }
+    }
+
+    fn mutate<R: Rng>(genome: &mut Traits, rng: &mut R, config: &Config<Self>, generation: u64) {
+        genome.mutate(rng, config, generation);
+    }
+
+    fn mutate_with_chance<R: Rng>(genome: &mut Traits, rng: &mut R, config: &Config<Self>, generation: u64, mutation_chance: f64) {
+        genome.mutate_with_chance(rng, config, generation, mutation_chance);
+    }
+
+    // the number genes are combined by the configured crossover operator and kept inside their bounds
+    fn crossover<R: Rng>(traits_one: &Traits, traits_two: &Traits, rng: &mut R, config: &Config<Self>) -> Traits {
+        let crossover = &config.crossover;
+        let bounds = &config.traits;
+
+        Traits {
+            number_of_averaging_periods: crossover.cross_u64(traits_one.number_of_averaging_periods, traits_two.number_of_averaging_periods, bounds.number_of_averaging_periods.min, bounds.number_of_averaging_periods.max, rng),
+            minimum_buy_momentum: crossover.cross_f64(traits_one.minimum_buy_momentum, traits_two.minimum_buy_momentum, bounds.minimum_buy_momentum.min, bounds.minimum_buy_momentum.max, rng),
+            maximum_buy_momentum: crossover.cross_f64(traits_one.maximum_buy_momentum, traits_two.maximum_buy_momentum, bounds.maximum_buy_momentum.min, bounds.maximum_buy_momentum.max, rng),
+            trailing_stop_loss: crossover.cross_f64(traits_one.trailing_stop_loss, traits_two.trailing_stop_loss, bounds.trailing_stop_loss.min, bounds.trailing_stop_loss.max, rng),
+            stop_loss: crossover.cross_f64(traits_one.stop_loss, traits_two.stop_loss, bounds.stop_loss.min, bounds.stop_loss.max, rng),
+            minimum_holding_periods: crossover.cross_u64(traits_one.minimum_holding_periods, traits_two.minimum_holding_periods, bounds.minimum_holding_periods.min, bounds.minimum_holding_periods.max, rng),
+            maximum_holding_periods: crossover.cross_u64(traits_one.maximum_holding_periods, traits_two.maximum_holding_periods, bounds.maximum_holding_periods.min, bounds.maximum_holding_periods.max, rng),
+            percent_purchase: crossover.cross_f64(traits_one.percent_purchase, traits_two.percent_purchase, bounds.percent_purchase.min, bounds.percent_purchase.max, rng),
+            target_sell_percentage: crossover.cross_f64(traits_one.target_sell_percentage, traits_two.target_sell_percentage, bounds.target_sell_percentage.min, bounds.target_sell_percentage.max, rng),
+            direction: pick(traits_one.direction, traits_two.direction, rng),
+            order_type: pick(traits_one.order_type, traits_two.order_type, rng),
+            entry_offset_percentage: crossover.cross_f64(traits_one.entry_offset_percentage, traits_two.entry_offset_percentage, bounds.entry_offset_percentage.min, bounds.entry_offset_percentage.max, rng),
+            order_expiry_periods: crossover.cross_u64(traits_one.order_expiry_periods, traits_two.order_expiry_periods, bounds.order_expiry_periods.min, bounds.order_expiry_periods.max, rng),
+            leverage: crossover.cross_f64(traits_one.leverage, traits_two.leverage, bounds.leverage.min, bounds.leverage.max, rng),
+            asset: pick(traits_one.asset, traits_two.asset, rng),
+            // children start from the average step size of their parents
+            step_sizes: average_step_sizes(&traits_one.step_sizes, &traits_two.step_sizes)
+        }
+    }
+
+    // Percent difference between all traits averaged over the nine original genes so the Hamming threshold keeps its meaning
+    // genes added since only add to it when they differ, a different direction, order type or asset counts as completely different
+    fn distance(traits_one: &Traits, traits_two: &Traits) -> f64 {
+        let differences = [
+            calculate_percent_difference(traits_one.number_of_averaging_periods as f64, traits_two.number_of_averaging_periods as f64),
+            calculate_percent_difference(traits_one.minimum_buy_momentum, traits_two.minimum_buy_momentum),
+            calculate_percent_difference(traits_one.maximum_buy_momentum, traits_two.maximum_buy_momentum),
+            calculate_percent_difference(traits_one.trailing_stop_loss, traits_two.trailing_stop_loss),
+            calculate_percent_difference(traits_one.stop_loss, traits_two.stop_loss),
+            calculate_percent_difference(traits_one.minimum_holding_periods as f64, traits_two.minimum_holding_periods as f64),
+            calculate_percent_difference(traits_one.maximum_holding_periods as f64, traits_two.maximum_holding_periods as f64),
+            calculate_percent_difference(traits_one.percent_purchase, traits_two.percent_purchase),
+            calculate_percent_difference(traits_one.target_sell_percentage, traits_two.target_sell_percentage),
+            if traits_one.direction == traits_two.direction { 0.0 } else { 100.0 },
+            if traits_one.order_type == traits_two.order_type { 0.0 } else { 100.0 },
+            calculate_percent_difference(traits_one.entry_offset_percentage, traits_two.entry_offset_percentage),
+            calculate_percent_difference(traits_one.order_expiry_periods as f64, traits_two.order_expiry_periods as f64),
+            calculate_percent_difference(traits_one.leverage, traits_two.leverage),
+            if traits_one.asset == traits_two.asset { 0.0 } else { 100.0 }
+        ];
+
+        differences.iter().sum::<f64>() / NUMBER_OF_ORIGINAL_GENES
+    }
+
+    fn validate_config(config: &TraitsConfig) -> Vec<ConfigError> {
+        config.validate()
+    }
+
+    fn tradeable_assets(config: &TraitsConfig) -> Vec<Asset> {
+        config.asset.allowed()
+    }
+}
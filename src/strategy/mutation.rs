@@ -0,0 +1,142 @@
+use rand::Rng;
+use crate::config::ConfigError;
+use crate::bot::traits::gen_range_or_min;
+
+// How a number gene changes when it mutates
+// Resample draws a new value from anywhere in the gene's range (the original behaviour)
+// Gaussian moves the gene by a normal step with a sigma of SigmaPercentage of the gene's range
+// SelfAdaptive carries a step size for every gene in the genome that mutates along with it (evolution strategy style)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum MutationOperator {
+    #[default]
+    Resample,
+    Gaussian,
+    SelfAdaptive
+}
+
+fn default_sigma_percentage() -> f64 {
+    10.0
+}
+
+fn default_minimum_step_percentage() -> f64 {
+    0.1
+}
+
+fn default_annealing_rate() -> f64 {
+    1.0
+}
+
+// The mutation chance and the Gaussian sigma are multiplied by the annealing rate every generation
+// so the search narrows as the run goes on, they never drop below the minimum annealing scale of where they started
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Mutation {
+    #[serde(default)]
+    pub operator: MutationOperator,
+    #[serde(default = "default_sigma_percentage")]
+    pub sigma_percentage: f64, // the Gaussian step and the starting self adaptive step as a percentage of the gene's range
+    #[serde(default = "default_minimum_step_percentage")]
+    pub minimum_step_percentage: f64, // stops self adaptive steps shrinking to nothing
+    #[serde(default = "default_annealing_rate")]
+    pub annealing_rate: f64,
+    #[serde(default)]
+    pub minimum_annealing_scale: f64
+}
+
+impl Default for Mutation {
+    fn default() -> Mutation {
+        Mutation {
+            operator: MutationOperator::Resample,
+            sigma_percentage: default_sigma_percentage(),
+            minimum_step_percentage: default_minimum_step_percentage(),
+            annealing_rate: default_annealing_rate(),
+            minimum_annealing_scale: 0.0
+        }
+    }
+}
+
+// Box-Muller, a draw from a normal distribution with a mean of 0 and a standard deviation of 1
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let uniform_one: f64 = 1.0 - rng.gen::<f64>(); // keeps it above 0 for the log
+    let uniform_two: f64 = rng.gen();
+
+    (-2.0 * uniform_one.ln()).sqrt() * (2.0 * std::f64::consts::PI * uniform_two).cos()
+}
+
+impl Mutation {
+    // the first generation is 1
+    pub fn annealing_scale(&self, generation: u64) -> f64 {
+        let generations_annealed = generation.saturating_sub(1).min(i32::MAX as u64) as i32;
+
+        self.annealing_rate.powi(generations_annealed).max(self.minimum_annealing_scale)
+    }
+
+    pub fn initial_step_size(&self) -> f64 {
+        self.sigma_percentage / 100.0
+    }
+
+    // the step size each gene is moved by as a fraction of its range
+    // self adaptive genomes bring their own
+    pub fn step_size(&self, generation: u64) -> f64 {
+        self.initial_step_size() * self.annealing_scale(generation)
+    }
+
+    // log-normal update, every step size shares one random factor and gets one of its own
+    // genomes from before self adaptive mutation have no step sizes so they start from the sigma
+    pub fn adapt_step_sizes<R: Rng>(&self, step_sizes: &mut [f64], rng: &mut R) {
+        let number_of_genes = step_sizes.len() as f64;
+        let shared_learning_rate = 1.0 / (2.0 * number_of_genes).sqrt();
+        let gene_learning_rate = 1.0 / (2.0 * number_of_genes.sqrt()).sqrt();
+        let shared_factor = shared_learning_rate * standard_normal(rng);
+
+        for step_size in step_sizes.iter_mut() {
+            if *step_size <= 0.0 {
+                *step_size = self.initial_step_size();
+            }
+
+            *step_size *= (shared_factor + (gene_learning_rate * standard_normal(rng))).exp();
+            *step_size = step_size.max(self.minimum_step_percentage / 100.0).min(1.0);
+        }
+    }
+
+    // moved genes are clamped to the bounds of the gene
+    pub fn mutate_f64<R: Rng>(&self, value: f64, min: f64, max: f64, step_size: f64, rng: &mut R) -> f64 {
+        match self.operator {
+            MutationOperator::Resample => gen_range_or_min(rng, min, max),
+            MutationOperator::Gaussian | MutationOperator::SelfAdaptive => {
+                let sigma = step_size * (max - min);
+                (value + (sigma * standard_normal(rng))).max(min).min(max)
+            }
+        }
+    }
+
+    // whole number genes are moved as numbers and rounded back to the nearest whole number
+    pub fn mutate_u64<R: Rng>(&self, value: u64, min: u64, max: u64, step_size: f64, rng: &mut R) -> u64 {
+        match self.operator {
+            MutationOperator::Resample => gen_range_or_min(rng, min, max),
+            _ => self.mutate_f64(value as f64, min as f64, max as f64, step_size, rng).round() as u64
+        }
+    }
+
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut config_errors = Vec::<ConfigError>::new();
+
+        if self.sigma_percentage <= 0.0 || self.sigma_percentage > 100.0 {
+            config_errors.push(ConfigError::new("Sigma Percentage must be greater then 0 and at most 100".to_string(), "Mutation.SigmaPercentage".to_string()));
+        }
+
+        if self.minimum_step_percentage < 0.0 || self.minimum_step_percentage > self.sigma_percentage {
+            config_errors.push(ConfigError::new("Minimum Step Percentage must be between 0 and the Sigma Percentage".to_string(), "Mutation.MinimumStepPercentage".to_string()));
+        }
+
+        if self.annealing_rate <= 0.0 || self.annealing_rate > 1.0 {
+            config_errors.push(ConfigError::new("Annealing Rate must be greater then 0 and at most 1".to_string(), "Mutation.AnnealingRate".to_string()));
+        }
+
+        if self.minimum_annealing_scale < 0.0 || self.minimum_annealing_scale > 1.0 {
+            config_errors.push(ConfigError::new("Minimum Annealing Scale must be between 0 and 1".to_string(), "Mutation.MinimumAnnealingScale".to_string()));
+        }
+
+        config_errors
+    }
+}
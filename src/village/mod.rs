@@ -21,7 +21,7 @@ impl ResourceType {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Village {
     pub resources: HashMap<ResourceType, u64>
 }
@@ -1,11 +1,30 @@
 use trading_sim::bot::Bot;
 use trading_sim::bot;
-use trading_sim::price_data::PriceData;
-use trading_sim::bot::traits::Traits;
-use trading_sim::bot::holdings::{CurrentHolding, SoldHolding, SellReason};
-use trading_sim::config::Config;
+use trading_sim::bot::fitness::{self, FitnessFunction, WeightedFitnessFunction, RunSummary, Metrics};
+use trading_sim::price_data::{PriceData, MarketHistory};
+use trading_sim::bot::traits::{Traits, Direction, NUMBER_OF_STEP_SIZES};
+use trading_sim::bot::holdings::{CurrentHolding, SoldHolding, SellReason, Side};
+use trading_sim::bot::orders::{Order, ClosedOrder, OrderType, OrderCloseReason};
+use trading_sim::config::{Config, ExecutionModel, IntrabarOrdering};
 use trading_sim::asset::Asset;
+use trading_sim::market::slippage::SlippageModel;
+use trading_sim::market::volume::UnfilledRemainder;
+use trading_sim::market::fees::{FeeSchedule, FeeTier, TradedVolume};
+use trading_sim::market::rules::TradingRules;
+use trading_sim::simulation::nsga2::{self, Objective};
+use trading_sim::simulation;
+use trading_sim::simulation::selection::{self, ParentSelection, SelectionMethod};
+use trading_sim::simulation::islands::{self, Topology};
+use trading_sim::simulation::speciation::{self, Speciation};
+use trading_sim::simulation::diversity::{self, DiversityAction};
+use trading_sim::simulation::stopping::{Progress, StoppingCriteria, StopReason};
+use trading_sim::simulation::hall_of_fame::HallOfFame;
+use trading_sim::strategy::{Strategy, Momentum};
+use trading_sim::strategy::crossover::{Crossover, CrossoverOperator};
+use trading_sim::strategy::mutation::{Mutation, MutationOperator};
+use trading_sim::bot::constraints::{ConstraintViolation, ViolationAction};
 use std::sync::Arc;
+use std::collections::HashMap;
 use std::fs;
 extern crate trading_sim;
 
@@ -28,21 +47,44 @@ mod bot_assets {
             minimum_holding_periods: 1,
             maximum_holding_periods: 30,
             percent_purchase: 90.0,
-            target_sell_percentage: 5.0
+            target_sell_percentage: 5.0,
+            direction: Direction::Up,
+            order_type: OrderType::Market,
+            entry_offset_percentage: 0.0,
+            order_expiry_periods: 1,
+            leverage: 1.0,
+            asset: Asset::ETH,
+            step_sizes: [0.1; NUMBER_OF_STEP_SIZES]
         }
     }
 
     fn generate_default_bot (traits: Traits) -> Bot {
         Bot {
             id: 0,
+            island: 0,
+            migrated_from: None,
             traits,
             money: 1000.0,
+            money_in_usd: 0.0,
             value_history: Vec::<bot::ValueHistory>::new(),
             current_holdings: Vec::<CurrentHolding>::new(),
             sold_holdings: Vec::<SoldHolding>::new(),
+            open_orders: Vec::<Order>::new(),
+            closed_orders: Vec::<ClosedOrder>::new(),
+            rejected_orders: 0,
             fitness: 0.0,
+            shared_fitness: None,
+            species: 0,
+            metrics: Metrics::default(),
+            pareto_rank: 0,
+            crowding_distance: 0.0,
+            violations: Vec::<ConstraintViolation>::new(),
+            disqualified: false,
+            periods_traded: 0,
+            periods_in_market: 0,
             start_time: None,
-            end_time: None
+            end_time: None,
+            traded_volume: TradedVolume::default()
         }
     }
 
@@ -78,12 +120,47 @@ mod bot_assets {
 
     fn generate_default_config () -> Arc<Config> {
         let config_as_yaml = fs::read_to_string("./tests/test_data/example_config.yaml").unwrap();
-        let config: Config = serde_yaml::from_str(&config_as_yaml.as_str()).unwrap();
+        let config: Config = serde_yaml::from_str(config_as_yaml.as_str()).unwrap();
         config.validate_config();
 
         Arc::new(config)
     }
 
+    #[test]
+    fn test_momentum_through_strategy() {
+        let config = generate_default_config();
+        let bounds = &config.traits;
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..50 {
+            let genome_one = Momentum::random_genome(&mut rng, &config);
+            let genome_two = Momentum::random_genome(&mut rng, &config);
+
+            for genome in [genome_one, genome_two] {
+                assert!((bounds.number_of_averaging_periods.min..=bounds.number_of_averaging_periods.max).contains(&genome.number_of_averaging_periods));
+                assert!((bounds.minimum_buy_momentum.min..=bounds.minimum_buy_momentum.max).contains(&genome.minimum_buy_momentum));
+                assert!((bounds.stop_loss.min..=bounds.stop_loss.max).contains(&genome.stop_loss));
+                assert!((bounds.maximum_holding_periods.min..=bounds.maximum_holding_periods.max).contains(&genome.maximum_holding_periods));
+                assert!((bounds.percent_purchase.min..=bounds.percent_purchase.max).contains(&genome.percent_purchase));
+            }
+
+            // uniform crossover copies every gene from one of the parents
+            let child = Momentum::crossover(&genome_one, &genome_two, &mut rng, &config);
+            assert!(child.number_of_averaging_periods == genome_one.number_of_averaging_periods || child.number_of_averaging_periods == genome_two.number_of_averaging_periods);
+            assert!(child.stop_loss == genome_one.stop_loss || child.stop_loss == genome_two.stop_loss);
+            assert!(child.percent_purchase == genome_one.percent_purchase || child.percent_purchase == genome_two.percent_purchase);
+
+            assert_relative_eq!(Momentum::distance(&genome_one, &genome_one), 0.0);
+            assert_relative_eq!(Momentum::distance(&genome_one, &genome_two), Momentum::distance(&genome_two, &genome_one), max_relative = 0.0001);
+        }
+
+        let traits = generate_default_traits();
+        let mut other_traits = traits;
+        other_traits.maximum_buy_momentum = 4.0;
+        other_traits.number_of_averaging_periods = 10;
+        assert_relative_eq!(Momentum::distance(&traits, &other_traits), 25.589, max_relative = 0.0001);
+    }
+
     #[test]
     fn test_bot_simple_cycle() {
         let traits = generate_default_traits();
@@ -91,7 +168,7 @@ mod bot_assets {
         let price_history = generate_price_history();
         let config = generate_default_config();
 
-        let price_history_as_arc = Arc::from(price_history);
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
 
         bot.run_period(&price_history_as_arc, 0, &config);
 
@@ -100,7 +177,7 @@ mod bot_assets {
         bot.run_period(&price_history_as_arc, 1, &config);
         assert_eq!(bot.current_holdings.len(), 1);
 
-        let first_holding = bot.current_holdings.get(0).unwrap();
+        let first_holding = bot.current_holdings.first().unwrap();
         assert_eq!(first_holding.asset, Asset::ETH);
         assert_relative_eq!(first_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_holding.money_spent, 906.29, max_relative = 0.001);
@@ -114,7 +191,7 @@ mod bot_assets {
         assert_eq!(bot.sold_holdings.len(), 1);
         assert_eq!(bot.current_holdings.len(), 0);
 
-        let first_sold_holding = bot.sold_holdings.get(0).unwrap();
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
         assert_eq!(first_sold_holding.asset, Asset::ETH);
         assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.money_spent, 906.29, max_relative = 0.001);
@@ -129,7 +206,7 @@ mod bot_assets {
         assert_relative_eq!(first_sold_holding.percent_gained, 6.34, max_relative = 0.001);
         assert_eq!(first_sold_holding.sell_time, 1515033000);
         assert_eq!(first_sold_holding.purchase_time, 1515033900);
-        assert_eq!(first_sold_holding.win, true);
+        assert!(first_sold_holding.win);
         assert_eq!(first_sold_holding.sell_reason, SellReason::Forced);
 
         assert_relative_eq!(bot.money, 1057.5, max_relative = 0.001);
@@ -146,7 +223,7 @@ mod bot_assets {
         let mut price_history = generate_price_history();
         let config = generate_default_config();
 
-        let mut price_point = price_history.get_mut(2).unwrap();
+        let price_point = price_history.get_mut(2).unwrap();
         price_point.close = 100.0;
         price_point.high = 105.0;
 
@@ -160,7 +237,7 @@ mod bot_assets {
         };
         price_history.push(fourth_price_point);
 
-        let price_history_as_arc = Arc::from(price_history);
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
 
         bot.run_period(&price_history_as_arc, 0, &config);
         bot.run_period(&price_history_as_arc, 1, &config);
@@ -169,7 +246,7 @@ mod bot_assets {
         assert_eq!(bot.sold_holdings.len(), 1);
         assert_eq!(bot.current_holdings.len(), 0);
 
-        let first_sold_holding = bot.sold_holdings.get(0).unwrap();
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
         assert_eq!(first_sold_holding.asset, Asset::ETH);
         assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.money_spent, 906.29, max_relative = 0.001);
@@ -182,7 +259,7 @@ mod bot_assets {
         assert_relative_eq!(first_sold_holding.money_from_sell, 876.19, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.amount_gained, -30.116, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.percent_gained, -3.323, max_relative = 0.001);
-        assert_eq!(first_sold_holding.win, false);
+        assert!(!first_sold_holding.win);
         assert_eq!(first_sold_holding.sell_reason, SellReason::StopLoss);
 
         assert_relative_eq!(bot.money, 969.9, max_relative = 0.001);
@@ -195,7 +272,7 @@ mod bot_assets {
         let mut price_history = generate_price_history();
         let config = generate_default_config();
 
-        let mut price_point = price_history.get_mut(2).unwrap();
+        let price_point = price_history.get_mut(2).unwrap();
         price_point.close = 103.0;
         price_point.high = 105.0;
 
@@ -209,7 +286,7 @@ mod bot_assets {
         };
         price_history.push(fourth_price_point);
 
-        let price_history_as_arc = Arc::from(price_history);
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
 
         bot.run_period(&price_history_as_arc, 0, &config);
         bot.run_period(&price_history_as_arc, 1, &config);
@@ -218,7 +295,7 @@ mod bot_assets {
         assert_eq!(bot.sold_holdings.len(), 1);
         assert_eq!(bot.current_holdings.len(), 0);
 
-        let first_sold_holding = bot.sold_holdings.get(0).unwrap();
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
         assert_eq!(first_sold_holding.asset, Asset::ETH);
         assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.money_spent, 906.29, max_relative = 0.001);
@@ -231,7 +308,7 @@ mod bot_assets {
         assert_relative_eq!(first_sold_holding.money_from_sell, 902.613, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.amount_gained, -3.838, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.percent_gained, -0.4235, max_relative = 0.001);
-        assert_eq!(first_sold_holding.win, false);
+        assert!(!first_sold_holding.win);
         assert_eq!(first_sold_holding.sell_reason, SellReason::TrailingStopLoss);
 
         assert_relative_eq!(bot.money, 996.161, max_relative = 0.001);
@@ -247,7 +324,7 @@ mod bot_assets {
         let mut price_history = generate_price_history();
         let config = generate_default_config();
 
-        let mut price_point = price_history.get_mut(2).unwrap();
+        let price_point = price_history.get_mut(2).unwrap();
         price_point.close = 105.0;
         price_point.high = 105.0;
 
@@ -270,7 +347,7 @@ mod bot_assets {
         };
         price_history.push(fifth_price_point);
 
-        let price_history_as_arc = Arc::from(price_history);
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
 
         bot.run_period(&price_history_as_arc, 0, &config);
         bot.run_period(&price_history_as_arc, 1, &config);
@@ -280,7 +357,7 @@ mod bot_assets {
         assert_eq!(bot.sold_holdings.len(), 1);
         assert_eq!(bot.current_holdings.len(), 0);
 
-        let first_sold_holding = bot.sold_holdings.get(0).unwrap();
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
         assert_eq!(first_sold_holding.asset, Asset::ETH);
         assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.money_spent, 906.29, max_relative = 0.001);
@@ -293,7 +370,7 @@ mod bot_assets {
         assert_relative_eq!(first_sold_holding.money_from_sell, 919.982, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.amount_gained, 13.685, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.percent_gained, 1.510, max_relative = 0.001);
-        assert_eq!(first_sold_holding.win, true);
+        assert!(first_sold_holding.win);
         assert_eq!(first_sold_holding.sell_reason, SellReason::MaxPeriodsHeld);
 
         assert_relative_eq!(bot.money, 1013.6851, max_relative = 0.001);
@@ -306,7 +383,7 @@ mod bot_assets {
         let mut price_history = generate_price_history();
         let config = generate_default_config();
 
-        let mut price_point = price_history.get_mut(2).unwrap();
+        let price_point = price_history.get_mut(2).unwrap();
         price_point.close = 105.0;
 
         let fourth_price_point = PriceData {
@@ -319,7 +396,7 @@ mod bot_assets {
         };
         price_history.push(fourth_price_point);
 
-        let price_history_as_arc = Arc::from(price_history);
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
 
         bot.run_period(&price_history_as_arc, 0, &config);
         bot.run_period(&price_history_as_arc, 1, &config);
@@ -328,7 +405,7 @@ mod bot_assets {
         assert_eq!(bot.sold_holdings.len(), 1);
         assert_eq!(bot.current_holdings.len(), 0);
 
-        let first_sold_holding = bot.sold_holdings.get(0).unwrap();
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
         assert_eq!(first_sold_holding.asset, Asset::ETH);
         assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.money_spent, 906.29, max_relative = 0.001);
@@ -342,31 +419,1659 @@ mod bot_assets {
         assert_relative_eq!(first_sold_holding.money_from_sell, 938.3819, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.amount_gained, 32.084, max_relative = 0.001);
         assert_relative_eq!(first_sold_holding.percent_gained, 3.540, max_relative = 0.001);
-        assert_eq!(first_sold_holding.win, true);
+        assert!(first_sold_holding.win);
         assert_eq!(first_sold_holding.sell_reason, SellReason::TargetedSellPrice);
         assert_relative_eq!(bot.money, 1032.084, max_relative = 0.001);
     }
 
     #[test]
-    fn test_hamming_no_difference() {
+    fn test_bot_short_target_sell_percent() {
+        let mut traits = generate_default_traits();
+        traits.direction = Direction::Down;
+
+        let mut bot = generate_default_bot(traits);
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.short_borrow_fee_as_percentage = 0.001;
+        let config = Arc::new(config);
+
+        let price_history = vec!(
+            PriceData { time: 1515030000, low: 99.0, high: 101.0, open: 100.0, close: 99.0, volume: 100.0 },
+            PriceData { time: 1515030900, low: 96.5, high: 99.0, open: 98.5, close: 97.0, volume: 100.0 },
+            PriceData { time: 1515031800, low: 93.0, high: 97.5, open: 97.0, close: 95.0, volume: 100.0 },
+            PriceData { time: 1515032700, low: 94.0, high: 96.0, open: 95.0, close: 95.0, volume: 100.0 }
+        );
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        assert_eq!(bot.current_holdings.len(), 1);
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_eq!(first_holding.side, Side::Short);
+        assert_relative_eq!(first_holding.amount, 9.1371, max_relative = 0.001);
+        assert_relative_eq!(first_holding.stop_loss, 99.485, max_relative = 0.001);
+        assert_relative_eq!(first_holding.trailing_stop_loss, 97.97, max_relative = 0.001);
+        assert_relative_eq!(first_holding.targeted_sell_price, 93.575, max_relative = 0.001);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.sold_holdings.len(), 1);
+        assert_eq!(bot.current_holdings.len(), 0);
+
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.side, Side::Short);
+        assert_relative_eq!(first_sold_holding.money_spent, 906.304, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.sell_price, 93.575, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.trailing_stop_loss, 95.95, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.borrow_fee, 1.754, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.sell_fee, 5.985, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.money_from_sell, 937.265, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.percent_gained, 3.416, max_relative = 0.001);
+        assert!(first_sold_holding.win);
+        assert_eq!(first_sold_holding.sell_reason, SellReason::TargetedSellPrice);
+
+        assert_relative_eq!(bot.money, 1030.961, max_relative = 0.001);
+    }
+
+    fn generate_intra_candle_config () -> Arc<Config> {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.execution_model = ExecutionModel::IntraCandle;
+
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_bot_intra_candle_trailing_stop_loss() {
         let traits = generate_default_traits();
-        let bot_one = generate_default_bot(traits);
-        let bot_two = generate_default_bot(traits);
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        let config = generate_intra_candle_config();
 
-        let hamming_value = bot_one.hamming(&bot_two);
-        assert_relative_eq!(hamming_value, 0.0, max_relative = 0.0001);
+        // wicks through the trailing stop and recovers, the close model would never stop out here
+        let price_point = price_history.get_mut(2).unwrap();
+        price_point.low = 103.0;
+        price_point.high = 106.0;
+        price_point.close = 106.0;
+
+        price_history.push(PriceData {
+            time: 1515039000,
+            low: 100.0,
+            high: 110.0,
+            open: 105.0,
+            close: 110.0,
+            volume: 100.0
+        });
+
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // the trailing stop only moves to the close once the holding survived the candle
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.trailing_stop_loss, 103.95, max_relative = 0.001);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.sold_holdings.len(), 1);
+        assert_eq!(bot.current_holdings.len(), 0);
+
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.sell_reason, SellReason::TrailingStopLoss);
+        assert_relative_eq!(first_sold_holding.sell_price, 103.95, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.sell_fee, 6.420, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.money_from_sell, 910.782, max_relative = 0.001);
+        assert_relative_eq!(bot.money, 1004.485, max_relative = 0.001);
     }
 
     #[test]
-    fn test_hamming_difference() {
+    fn test_bot_intra_candle_stop_loss_gap() {
         let traits = generate_default_traits();
-        let bot_one = generate_default_bot(traits);
-        let mut bot_two = generate_default_bot(traits);
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        let config = generate_intra_candle_config();
 
-        bot_two.traits.maximum_buy_momentum = 4.0;
-        bot_two.traits.number_of_averaging_periods = 10;
+        // opens below the stop loss so the stop fills at the open instead of the stop price
+        let price_point = price_history.get_mut(2).unwrap();
+        price_point.open = 100.5;
+        price_point.low = 100.0;
+        price_point.high = 101.0;
+        price_point.close = 101.0;
 
-        let hamming_value = bot_one.hamming(&bot_two);
-        assert_relative_eq!(hamming_value, 25.589, max_relative = 0.0001);
+        price_history.push(PriceData {
+            time: 1515039000,
+            low: 100.0,
+            high: 110.0,
+            open: 105.0,
+            close: 110.0,
+            volume: 100.0
+        });
+
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.sold_holdings.len(), 1);
+
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.sell_reason, SellReason::StopLoss);
+        assert_relative_eq!(first_sold_holding.sell_price, 100.5, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.money_from_sell, 880.554, max_relative = 0.001);
+        assert_relative_eq!(bot.money, 974.257, max_relative = 0.001);
+    }
+
+    #[test]
+    fn test_bot_target_and_stop_loss_same_period() {
+        let expected_results = vec!(
+            (IntrabarOrdering::Optimistic, SellReason::TargetedSellPrice, 107.1),
+            (IntrabarOrdering::Pessimistic, SellReason::StopLoss, 100.0),
+            // the candle closes below its open so the high is assumed to come first
+            (IntrabarOrdering::OpenToClose, SellReason::TargetedSellPrice, 107.1)
+        );
+
+        for (intrabar_ordering, sell_reason, sell_price) in expected_results {
+            let traits = generate_default_traits();
+            let mut bot = generate_default_bot(traits);
+            let mut price_history = generate_price_history();
+
+            let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+            config.intrabar_ordering = intrabar_ordering;
+            let config = Arc::new(config);
+
+            let price_point = price_history.get_mut(2).unwrap();
+            price_point.low = 99.0;
+            price_point.high = 110.0;
+            price_point.close = 100.0;
+
+            price_history.push(PriceData {
+                time: 1515039000,
+                low: 100.0,
+                high: 110.0,
+                open: 105.0,
+                close: 110.0,
+                volume: 100.0
+            });
+
+            let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+            bot.run_period(&price_history_as_arc, 0, &config);
+            bot.run_period(&price_history_as_arc, 1, &config);
+            bot.run_period(&price_history_as_arc, 2, &config);
+
+            assert_eq!(bot.sold_holdings.len(), 1);
+
+            let first_sold_holding = bot.sold_holdings.first().unwrap();
+            assert_eq!(first_sold_holding.sell_reason, sell_reason);
+            assert_relative_eq!(first_sold_holding.sell_price, sell_price, max_relative = 0.001);
+            assert_eq!(first_sold_holding.intrabar_ordering, Some(intrabar_ordering));
+        }
+    }
+
+    fn generate_limit_order_price_history (low: f64) -> Vec<PriceData> {
+        let mut price_history = generate_price_history();
+
+        let price_point = price_history.get_mut(2).unwrap();
+        price_point.low = low;
+        price_point.high = 104.0;
+        price_point.close = 103.0;
+
+        price_history.push(PriceData {
+            time: 1515039000,
+            low: 100.0,
+            high: 110.0,
+            open: 105.0,
+            close: 110.0,
+            volume: 100.0
+        });
+
+        price_history
+    }
+
+    #[test]
+    fn test_bot_limit_order_fills() {
+        let mut traits = generate_default_traits();
+        traits.order_type = OrderType::Limit;
+        traits.entry_offset_percentage = 1.0;
+        traits.order_expiry_periods = 2;
+
+        let mut bot = generate_default_bot(traits);
+        let config = generate_default_config();
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_limit_order_price_history(100.5)));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // the period never traded down to the limit so the order waits
+        assert_eq!(bot.current_holdings.len(), 0);
+        assert_eq!(bot.open_orders.len(), 1);
+        let order = bot.open_orders.first().unwrap();
+        assert_relative_eq!(order.price, 100.98, max_relative = 0.001);
+        assert_relative_eq!(order.money_reserved, 900.0, max_relative = 0.001);
+        assert_relative_eq!(bot.money, 100.0, max_relative = 0.001);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.open_orders.len(), 0);
+        assert_eq!(bot.closed_orders.len(), 0);
+        assert_eq!(bot.current_holdings.len(), 1);
+
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.purchase_price, 100.98, max_relative = 0.001);
+        assert_relative_eq!(first_holding.amount, 8.9127, max_relative = 0.001);
+        assert_eq!(first_holding.purchase_time, 1515033000);
+        assert_relative_eq!(bot.money, 93.696, max_relative = 0.001);
+    }
+
+    #[test]
+    fn test_bot_limit_order_expires() {
+        let mut traits = generate_default_traits();
+        traits.order_type = OrderType::Limit;
+        traits.entry_offset_percentage = 1.0;
+        traits.order_expiry_periods = 2;
+
+        let mut bot = generate_default_bot(traits);
+        let config = generate_default_config();
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_limit_order_price_history(102.0)));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.current_holdings.len(), 0);
+        assert_eq!(bot.open_orders.len(), 0);
+        assert_eq!(bot.closed_orders.len(), 1);
+
+        let closed_order = bot.closed_orders.first().unwrap();
+        assert_eq!(closed_order.close_reason, OrderCloseReason::Expired);
+        assert_eq!(closed_order.close_time, 1515033000);
+        assert_relative_eq!(bot.money, 1000.0, max_relative = 0.001);
+    }
+
+    #[test]
+    fn test_bot_fixed_bps_slippage() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.slippage.model = SlippageModel::FixedBps;
+        config.slippage.bps = 10.0;
+        let config = Arc::new(config);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // buying pushes the price up
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.purchase_price, 102.102, max_relative = 0.0001);
+        assert_relative_eq!(first_holding.amount, 8.8147, max_relative = 0.0001);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        // selling pushes the price down
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_relative_eq!(first_sold_holding.sell_price, 109.89, max_relative = 0.0001);
+        assert_relative_eq!(first_sold_holding.money_from_sell, 961.867, max_relative = 0.0001);
+        assert_relative_eq!(bot.money, 1055.568, max_relative = 0.0001);
+    }
+
+    fn generate_volume_constrained_config (participation_rate: f64) -> Arc<Config> {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.volume_constraint.enabled = true;
+        config.volume_constraint.participation_rate = participation_rate;
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_bot_volume_constrained_partial_fill() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_volume_constrained_config(0.05);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // only 5 of the 8.8235 wanted trade in the period, the rest of the order expires
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.amount, 5.0, max_relative = 0.0001);
+        assert_relative_eq!(first_holding.money_spent, 513.57, max_relative = 0.0001);
+        assert!(first_holding.partial_fill);
+
+        assert_eq!(bot.open_orders.len(), 0);
+        let closed_order = bot.closed_orders.first().unwrap();
+        assert_eq!(closed_order.close_reason, OrderCloseReason::Expired);
+        assert_relative_eq!(closed_order.order.amount_filled, 5.0, max_relative = 0.0001);
+        assert_relative_eq!(bot.money, 486.43, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_bot_volume_constrained_cancel_remainder() {
+        let mut traits = generate_default_traits();
+        traits.order_expiry_periods = 5;
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+
+        let mut config = Arc::try_unwrap(generate_volume_constrained_config(0.05)).unwrap();
+        config.volume_constraint.unfilled_remainder = UnfilledRemainder::Cancel;
+        let config = Arc::new(config);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        assert_eq!(bot.open_orders.len(), 0);
+        assert_eq!(bot.closed_orders.first().unwrap().close_reason, OrderCloseReason::Cancelled);
+    }
+
+    #[test]
+    fn test_bot_volume_constrained_partial_exit() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        price_history.push(PriceData { time: 1515032100, low: 108.0, high: 112.0, open: 110.0, close: 111.0, volume: 100.0 });
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+        let config = generate_default_config();
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // the target is hit but only 5 of the 8.8235 held can be sold
+        let config = generate_volume_constrained_config(0.05);
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.sell_reason, SellReason::TargetedSellPrice);
+        assert_relative_eq!(first_sold_holding.amount, 5.0, max_relative = 0.0001);
+        assert!(first_sold_holding.partial_exit);
+
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.amount, 3.8235, max_relative = 0.0001);
+        assert_relative_eq!(first_holding.money_spent + first_sold_holding.money_spent, 906.29, max_relative = 0.0001);
+    }
+
+    fn generate_fee_schedule_config (minimum_fee: f64) -> Arc<Config> {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.fee_schedule = Some(FeeSchedule {
+            tiers: vec!(
+                FeeTier { minimum_volume: 0.0, maker_fee_as_percentage: 0.001, taker_fee_as_percentage: 0.002 },
+                FeeTier { minimum_volume: 500.0, maker_fee_as_percentage: 0.0, taker_fee_as_percentage: 0.001 }
+            ),
+            minimum_fee,
+            volume_window_days: 30
+        });
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_bot_fee_schedule_tiers() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_fee_schedule_config(0.0);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // nothing has been traded yet so the buy pays the first tier's taker fee
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.buy_fee, 1.8, max_relative = 0.001);
+        assert_eq!(first_holding.buy_fee_tier, 0);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        // the buy pushed the traded volume into the second tier
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_relative_eq!(first_sold_holding.sell_fee, 0.9706, max_relative = 0.001);
+        assert_eq!(first_sold_holding.buy_fee_tier, 0);
+        assert_eq!(first_sold_holding.sell_fee_tier, 1);
+    }
+
+    #[test]
+    fn test_bot_fee_schedule_minimum_fee() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_fee_schedule_config(5.0);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.buy_fee, 5.0, max_relative = 0.001);
+    }
+
+    #[test]
+    fn test_bot_leverage() {
+        let mut traits = generate_default_traits();
+        traits.leverage = 2.0;
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_default_config();
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // half of the position is borrowed so only the margin and the fee are spent
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.amount, 17.6471, max_relative = 0.0001);
+        assert_relative_eq!(first_holding.borrowed, 900.0, max_relative = 0.0001);
+        assert_relative_eq!(first_holding.money_spent, 912.6, max_relative = 0.0001);
+
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        // the gain is doubled once the borrowed money is paid back
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_relative_eq!(first_sold_holding.money_from_sell, 1027.58, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_bot_liquidation() {
+        let mut traits = generate_default_traits();
+        traits.leverage = 5.0;
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        price_history[2] = PriceData { time: 1515033000, low: 88.0, high: 104.0, open: 104.0, close: 89.0, volume: 100.0 };
+        price_history.push(PriceData { time: 1515032100, low: 88.0, high: 90.0, open: 89.0, close: 90.0, volume: 100.0 });
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.margin.maintenance_margin_as_percentage = 0.1;
+        let config = Arc::new(config);
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        // the close is below the liquidation price of 90.67 so the position is liquidated before the stop loss
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.sell_reason, SellReason::Liquidated);
+        assert_relative_eq!(first_sold_holding.sell_price, 89.0, max_relative = 0.0001);
+        assert_eq!(bot.current_holdings.len(), 0);
+    }
+
+    fn generate_multi_asset_market_history (quote_asset: Asset) -> MarketHistory {
+        let eth_price_history = vec!(
+            PriceData { time: 1515030000, low: 99.0, high: 101.0, open: 100.0, close: 100.0, volume: 100.0 },
+            PriceData { time: 1515030900, low: 99.0, high: 101.0, open: 100.0, close: 100.0, volume: 100.0 },
+            PriceData { time: 1515031800, low: 99.0, high: 101.0, open: 100.0, close: 100.0, volume: 100.0 },
+            PriceData { time: 1515032700, low: 99.0, high: 101.0, open: 100.0, close: 100.0, volume: 100.0 },
+            PriceData { time: 1515033600, low: 99.0, high: 101.0, open: 100.0, close: 100.0, volume: 100.0 }
+        );
+        // BTC is missing the second period so it is dropped from both
+        let btc_price_history = vec!(
+            PriceData { time: 1515032700, low: 1040.0, high: 1060.0, open: 1050.0, close: 1055.0, volume: 10.0 },
+            PriceData { time: 1515030000, low: 990.0, high: 1010.0, open: 1000.0, close: 1000.0, volume: 10.0 },
+            PriceData { time: 1515031800, low: 1015.0, high: 1035.0, open: 1020.0, close: 1030.0, volume: 10.0 },
+            PriceData { time: 1515033600, low: 1050.0, high: 1070.0, open: 1060.0, close: 1065.0, volume: 10.0 }
+        );
+
+        let mut price_histories = HashMap::new();
+        price_histories.insert(Asset::ETH, eth_price_history);
+        price_histories.insert(Asset::BTC, btc_price_history);
+
+        MarketHistory::new(price_histories, quote_asset)
+    }
+
+    #[test]
+    fn test_market_history_aligned_on_time() {
+        let market_history = generate_multi_asset_market_history(Asset::USD);
+
+        assert_eq!(market_history.len(), 4);
+        assert_eq!(market_history.time(1), Some(1515031800));
+        assert_relative_eq!(market_history.price_data(Asset::BTC, 1).unwrap().open, 1020.0);
+        assert_relative_eq!(market_history.price_data(Asset::ETH, 1).unwrap().open, 100.0);
+    }
+
+    #[test]
+    fn test_bot_trades_asset_gene() {
+        let mut traits = generate_default_traits();
+        traits.asset = Asset::BTC;
+        let mut bot = generate_default_bot(traits);
+        let market_history = Arc::new(generate_multi_asset_market_history(Asset::USD));
+        let config = generate_default_config();
+
+        for period in 0..4 {
+            bot.run_period(&market_history, period, &config);
+        }
+
+        // the momentum and prices all come from BTC, ETH never moved
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.asset, Asset::BTC);
+        assert_relative_eq!(first_sold_holding.purchase_price, 1020.0);
+        assert_relative_eq!(first_sold_holding.sell_price, 1065.0);
+        assert_eq!(first_sold_holding.sell_reason, SellReason::Forced);
+    }
+
+    #[test]
+    fn test_market_history_cross_pair() {
+        let market_history = generate_multi_asset_market_history(Asset::BTC);
+
+        // ETH is priced in BTC and BTC itself can't be traded
+        assert_eq!(market_history.quote_asset(), Asset::BTC);
+        assert!(market_history.price_history(Asset::BTC).is_none());
+        assert_relative_eq!(market_history.price_data(Asset::ETH, 1).unwrap().open, 100.0 / 1020.0);
+        // the low and high are the widest the cross rate could have been over the period
+        assert_relative_eq!(market_history.price_data(Asset::ETH, 1).unwrap().low, 99.0 / 1035.0);
+        assert_relative_eq!(market_history.price_data(Asset::ETH, 1).unwrap().high, 101.0 / 1015.0);
+        assert_relative_eq!(market_history.quote_in_usd_at_open(1), 1020.0);
+    }
+
+    #[test]
+    fn test_bot_trades_cross_pair() {
+        let mut traits = generate_default_traits();
+        traits.direction = Direction::Down;
+        let mut bot = generate_default_bot(traits);
+        let market_history = Arc::new(generate_multi_asset_market_history(Asset::BTC));
+        let config = generate_default_config();
+
+        for period in 0..4 {
+            bot.run_period(&market_history, period, &config);
+        }
+
+        // ETH held its USD price while BTC rallied so ETH/BTC fell and the short made money
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.side, Side::Short);
+        assert_relative_eq!(first_sold_holding.purchase_price, 100.0 / 1020.0);
+        assert!(first_sold_holding.win);
+        assert_relative_eq!(bot.money_in_usd, bot.money * 1065.0);
+    }
+
+    #[test]
+    fn test_quote_asset_can_not_be_traded() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        let number_of_errors = config.validate_config().len();
+
+        // ETH is allowed by default
+        config.quote_asset = Asset::BTC;
+        assert_eq!(config.validate_config().len(), number_of_errors);
+
+        config.quote_asset = Asset::ETH;
+        assert_eq!(config.validate_config().len(), number_of_errors + 1);
+    }
+
+    fn generate_trading_rules_config (trading_rules: TradingRules) -> Arc<Config> {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.trading_rules.insert(Asset::ETH, trading_rules);
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_round_amount_without_lot_step() {
+        // without a lot step amounts are rounded to the nearest four decimal places like they always were
+        let trading_rules = TradingRules::default();
+        assert_relative_eq!(trading_rules.round_amount(8.82357), 8.8236);
+        assert_relative_eq!(trading_rules.amount_that_fits(8.8236, 5.12345), 5.1234);
+
+        let trading_rules = TradingRules { lot_step: Some(0.0001), ..TradingRules::default() };
+        assert_relative_eq!(trading_rules.round_amount(8.82357), 8.8235);
+    }
+
+    #[test]
+    fn test_bot_lot_step() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_trading_rules_config(TradingRules { lot_step: Some(0.1), ..TradingRules::default() });
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        assert_relative_eq!(bot.current_holdings.first().unwrap().amount, 8.8, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_bot_maximum_order_size() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_trading_rules_config(TradingRules { maximum_order_size: Some(5.0), ..TradingRules::default() });
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        // the order is capped and the money it didn't need is given back
+        let first_holding = bot.current_holdings.first().unwrap();
+        assert_relative_eq!(first_holding.amount, 5.0, max_relative = 0.0001);
+        assert!(!first_holding.partial_fill);
+        assert_eq!(bot.open_orders.len(), 0);
+        assert_relative_eq!(bot.money, 486.43, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_bot_minimum_notional_rejected() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_trading_rules_config(TradingRules { minimum_notional: 1000.0, ..TradingRules::default() });
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+
+        assert_eq!(bot.rejected_orders, 1);
+        assert_eq!(bot.open_orders.len(), 0);
+        assert_eq!(bot.current_holdings.len(), 0);
+        assert_relative_eq!(bot.money, 1000.0);
+    }
+
+    #[test]
+    fn test_bot_target_sell_on_price_tick() {
+        // the target of 107.1 is a resting sell so it's moved up to the next tick rather then the nearest one
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        let config = generate_trading_rules_config(TradingRules { price_tick: Some(0.25), ..TradingRules::default() });
+
+        price_history.get_mut(2).unwrap().close = 105.0;
+        price_history.push(PriceData {
+            time: 1515033000,
+            low: 100.0,
+            high: 110.0,
+            open: 105.0,
+            close: 110.0,
+            volume: 100.0
+        });
+
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+
+        bot.run_period(&price_history_as_arc, 0, &config);
+        bot.run_period(&price_history_as_arc, 1, &config);
+        bot.run_period(&price_history_as_arc, 2, &config);
+
+        assert_eq!(bot.sold_holdings.len(), 1);
+
+        let first_sold_holding = bot.sold_holdings.first().unwrap();
+        assert_eq!(first_sold_holding.sell_reason, SellReason::TargetedSellPrice);
+        assert_relative_eq!(first_sold_holding.amount, 8.8235, max_relative = 0.001);
+        assert_relative_eq!(first_sold_holding.sell_fee, 6.6242, max_relative = 0.0001);
+        assert_relative_eq!(first_sold_holding.money_from_sell + first_sold_holding.sell_fee, first_sold_holding.amount * 107.25, max_relative = 0.00001);
+    }
+
+    fn generate_equity_curve_config (sample_every_periods: u64) -> Arc<Config> {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.equity_curve.enabled = true;
+        config.equity_curve.sample_every_periods = sample_every_periods;
+        Arc::new(config)
+    }
+
+    #[test]
+    fn test_bot_equity_curve() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_equity_curve_config(1);
+
+        for period in 0..3 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        assert_eq!(bot.value_history.len(), 3);
+        assert_relative_eq!(bot.value_history[0].value, 1000.0);
+
+        // the holding is valued at the close of the period
+        let value_history = &bot.value_history[1];
+        assert_relative_eq!(value_history.cash, 93.71, max_relative = 0.001);
+        assert_relative_eq!(value_history.position_value, 926.47, max_relative = 0.001);
+        assert_relative_eq!(value_history.value, value_history.cash + value_history.position_value);
+
+        let value_history = &bot.value_history[2];
+        assert_relative_eq!(value_history.position_value, 0.0);
+        assert_relative_eq!(value_history.value, bot.money);
+    }
+
+    #[test]
+    fn test_bot_equity_curve_downsampled() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let mut price_history = generate_price_history();
+        price_history.push(PriceData { time: 1515032100, low: 108.0, high: 112.0, open: 110.0, close: 111.0, volume: 100.0 });
+        let price_history_as_arc = Arc::new(MarketHistory::from(price_history));
+        let config = generate_equity_curve_config(2);
+
+        for period in 0..4 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        // periods 0 and 2 are sampled and the final period is always kept
+        let times: Vec<u64> = bot.value_history.iter().map(|value_history| value_history.time).collect();
+        assert_eq!(times, vec!(1515034800, 1515033000, 1515032100));
+    }
+
+    #[test]
+    fn test_fitness_max_drawdown() {
+        let equity = vec!(100.0, 120.0, 90.0, 130.0);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 0.0, benchmark: None, benchmark_return: None, exposure: 0.0 };
+
+        assert_relative_eq!(fitness::calculate_max_drawdown(&equity), 0.25);
+        assert_relative_eq!(fitness::calculate_return_over_max_drawdown(&summary), 1.2);
+    }
+
+    #[test]
+    fn test_fitness_sharpe_and_sortino() {
+        let equity = vec!(100.0, 110.0, 99.0, 108.9);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 0.0, benchmark: None, benchmark_return: None, exposure: 0.0 };
+
+        assert_relative_eq!(fitness::calculate_sharpe(&summary), 0.35355, max_relative = 0.0001);
+        assert_relative_eq!(fitness::calculate_sortino(&summary), 0.57735, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_fitness_cagr_and_calmar() {
+        let equity = vec!(100.0, 80.0, 121.0);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 2.0, benchmark: None, benchmark_return: None, exposure: 0.0 };
+
+        assert_relative_eq!(fitness::calculate_cagr(&summary), 0.1, max_relative = 0.0001);
+        assert_relative_eq!(fitness::calculate_calmar(&summary), 0.5, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_fitness_against_benchmark() {
+        let equity = vec!(100.0, 110.0, 99.0);
+        let benchmark = vec!(100.0, 105.0, 102.9);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 0.0, benchmark: Some(&benchmark), benchmark_return: Some(0.029), exposure: 0.0 };
+
+        assert_relative_eq!(fitness::calculate_excess_return(&summary), -0.039, max_relative = 0.0001);
+        assert_relative_eq!(fitness::calculate_alpha(&summary), -0.042857, max_relative = 0.0001);
+        assert_relative_eq!(fitness::calculate_information_ratio(&summary), -0.230769, max_relative = 0.0001);
+
+        // nothing to compare against
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 0.0, benchmark: None, benchmark_return: None, exposure: 0.0 };
+        assert_relative_eq!(fitness::calculate_alpha(&summary), 0.0);
+    }
+
+    #[test]
+    fn test_fitness_when_the_money_runs_out() {
+        let equity = vec!(100.0, 0.0, 0.0);
+        let benchmark = vec!(100.0, 105.0, 102.9);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 1.0, benchmark: Some(&benchmark), benchmark_return: Some(0.029), exposure: 1.0 };
+
+        assert_relative_eq!(fitness::calculate_max_drawdown(&equity), 1.0);
+        assert_relative_eq!(fitness::calculate_cagr(&summary), -1.0);
+
+        let metrics = Metrics::new(&summary);
+        assert_relative_eq!(metrics.total_return, -1.0);
+        assert!(metrics.alpha.is_finite() && metrics.information_ratio.is_finite());
+
+        let fitness_functions = [FitnessFunction::FinalMoney, FitnessFunction::Sharpe, FitnessFunction::Sortino, FitnessFunction::Calmar, FitnessFunction::Cagr,
+            FitnessFunction::ReturnOverMaxDrawdown, FitnessFunction::Alpha, FitnessFunction::ExcessReturn, FitnessFunction::InformationRatio];
+        for fitness_function in fitness_functions {
+            assert!(fitness_function.calculate(&summary).is_finite());
+        }
+    }
+
+    #[test]
+    fn test_fitness_cagr_over_a_few_hours() {
+        let equity = vec!(100.0, 150.0, 200.0);
+        let summary = RunSummary { equity: &equity, sold_holdings: &[], years: 3.0 / (365.0 * 24.0), benchmark: None, benchmark_return: None, exposure: 0.0 };
+
+        // doubling every three hours overflows when it's compounded over a year so it's capped like the ratios
+        assert_relative_eq!(fitness::calculate_cagr(&summary), 100.0);
+        assert_relative_eq!(fitness::calculate_calmar(&summary), 100.0);
+
+        // the two capped functions can't cancel out to nothing that isn't a number
+        let composite = FitnessFunction::Composite(vec!(
+            WeightedFitnessFunction { function: FitnessFunction::Cagr, weight: 1.0 },
+            WeightedFitnessFunction { function: FitnessFunction::Cagr, weight: -1.0 }
+        ));
+        assert_relative_eq!(composite.calculate(&summary), 0.0);
+    }
+
+    #[test]
+    fn test_baseline_buy_and_hold() {
+        let mut market_history = generate_multi_asset_market_history(Asset::USD);
+        market_history.set_baseline(Asset::BTC, 1000.0);
+
+        // one BTC is bought at the open of the first period
+        let baseline = market_history.baseline().unwrap();
+        assert_relative_eq!(baseline.value_at(1515031800).unwrap(), 1030.0);
+        assert_eq!(baseline.equity(&[1515031800], 1515033600), Some(vec!(1000.0, 1030.0, 1065.0)));
+        assert_eq!(baseline.equity(&[1515030900], 1515033600), None);
+
+        // holding the quote currency is holding the money
+        let mut market_history = generate_multi_asset_market_history(Asset::BTC);
+        market_history.set_baseline(Asset::BTC, 1000.0);
+        assert_relative_eq!(market_history.baseline().unwrap().value_at(1515033600).unwrap(), 1000.0);
+    }
+
+    #[test]
+    fn test_bot_composite_fitness() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.fitness_function = FitnessFunction::Composite(vec!(
+            WeightedFitnessFunction { function: FitnessFunction::FinalMoney, weight: 1.0 },
+            WeightedFitnessFunction { function: FitnessFunction::ProfitFactor, weight: 2.0 }
+        ));
+        let config = Arc::new(config);
+
+        for period in 0..3 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        // the only trade won so the profit factor is capped at 100
+        assert_relative_eq!(bot.fitness, bot.money + 200.0, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_nsga2_non_dominated_sort() {
+        // bigger is better on both objectives
+        let values = vec!(
+            vec!(1.0, 5.0),
+            vec!(2.0, 4.0),
+            vec!(1.0, 4.0),
+            vec!(3.0, 1.0),
+            vec!(0.0, 0.0)
+        );
+
+        let fronts = nsga2::non_dominated_sort(&values);
+        assert_eq!(fronts, vec!(vec!(0, 1, 3), vec!(2), vec!(4)));
+    }
+
+    #[test]
+    fn test_nsga2_crowding_distance() {
+        let values = vec!(
+            vec!(0.0, 4.0),
+            vec!(1.0, 3.0),
+            vec!(3.0, 1.0),
+            vec!(4.0, 0.0)
+        );
+
+        // the edges are always kept and the rest are spread by the gap around them on each objective
+        let distances = nsga2::crowding_distance(&values, &[0, 1, 2, 3]);
+        assert_eq!(distances[0], f64::MAX);
+        assert_eq!(distances[3], f64::MAX);
+        assert_relative_eq!(distances[1], 1.5);
+        assert_relative_eq!(distances[2], 1.5);
+    }
+
+    #[test]
+    fn test_nsga2_rank_bots() {
+        let traits = generate_default_traits();
+        let mut bots = vec!(generate_default_bot(traits), generate_default_bot(traits), generate_default_bot(traits));
+        bots[0].metrics = Metrics { total_return: 0.5, max_drawdown: 0.4, number_of_trades: 10, ..Metrics::default() };
+        bots[1].metrics = Metrics { total_return: 0.2, max_drawdown: 0.1, number_of_trades: 10, ..Metrics::default() };
+        bots[2].metrics = Metrics { total_return: 0.1, max_drawdown: 0.2, number_of_trades: 10, ..Metrics::default() };
+
+        // the lower drawdown puts the second bot on the front with the first even though it made less
+        nsga2::rank(&mut bots, &[Objective::Return, Objective::MaxDrawdown]);
+        assert_eq!(bots[0].pareto_rank, 0);
+        assert_eq!(bots[1].pareto_rank, 0);
+        assert_eq!(bots[2].pareto_rank, 1);
+
+        bots.sort_by(nsga2::crowded_comparison);
+        assert_eq!(bots[2].metrics.total_return, 0.1);
+    }
+
+    #[test]
+    fn test_bot_metrics() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+        let config = generate_default_config();
+
+        for period in 0..3 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        assert_eq!(bot.metrics.number_of_trades, bot.sold_holdings.len() as u64);
+        assert_relative_eq!(bot.metrics.total_return, (bot.money / config.starting_money) - 1.0, max_relative = 0.0001);
+        assert_relative_eq!(bot.metrics.excess_return, 0.0);
+    }
+
+    #[test]
+    fn test_bot_excess_return_over_baseline() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let mut market_history = MarketHistory::from(generate_price_history());
+        let config = generate_equity_curve_config(1);
+        market_history.set_baseline(Asset::ETH, config.starting_money);
+        let market_history = Arc::new(market_history);
+
+        for period in 0..3 {
+            bot.run_period(&market_history, period, &config);
+        }
+
+        let baseline_return = (market_history.baseline().unwrap().value_at(bot.end_time.unwrap()).unwrap() / config.starting_money) - 1.0;
+        assert_relative_eq!(bot.metrics.excess_return, bot.metrics.total_return - baseline_return, max_relative = 0.0001);
+
+        // without an equity curve the baseline is taken at the times of the sells instead
+        let mut bot = generate_default_bot(traits);
+        let config = generate_default_config();
+        for period in 0..3 {
+            bot.run_period(&market_history, period, &config);
+        }
+
+        let baseline_return = (market_history.baseline().unwrap().value_at(bot.end_time.unwrap()).unwrap() / config.starting_money) - 1.0;
+        assert_relative_eq!(bot.metrics.excess_return, bot.metrics.total_return - baseline_return, max_relative = 0.0001);
+        assert!(FitnessFunction::ExcessReturn.validate(false).is_empty());
+    }
+
+    #[test]
+    fn test_activity_constraints_check() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.activity_constraints.minimum_trades = Some(10);
+        config.activity_constraints.maximum_exposure = Some(0.5);
+        config.activity_constraints.maximum_drawdown = Some(0.2);
+
+        let metrics = Metrics { number_of_trades: 2, exposure: 0.4, max_drawdown: 0.3, ..Metrics::default() };
+        let violations = config.activity_constraints.check(&metrics);
+
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].0, ConstraintViolation::TooFewTrades);
+        assert_relative_eq!(violations[0].1, 0.8);
+        assert_eq!(violations[1].0, ConstraintViolation::TooMuchDrawdown);
+        assert_relative_eq!(violations[1].1, 0.5, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_bot_disqualified_by_activity_constraints() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.activity_constraints.minimum_trades = Some(4);
+        let config = Arc::new(config);
+
+        for period in 0..3 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        assert_eq!(bot.violations, vec!(ConstraintViolation::TooFewTrades));
+        assert!(bot.disqualified);
+        assert_relative_eq!(bot.fitness, 0.0);
+    }
+
+    #[test]
+    fn test_bot_penalised_by_activity_constraints() {
+        let traits = generate_default_traits();
+        let mut bot = generate_default_bot(traits);
+        let price_history_as_arc = Arc::new(MarketHistory::from(generate_price_history()));
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.activity_constraints.minimum_trades = Some(4);
+        config.activity_constraints.action = ViolationAction::Penalty;
+        config.activity_constraints.penalty = 100.0;
+        let config = Arc::new(config);
+
+        for period in 0..3 {
+            bot.run_period(&price_history_as_arc, period, &config);
+        }
+
+        // one trade is three quarters short of the minimum
+        assert_eq!(bot.sold_holdings.len(), 1);
+        assert!(!bot.disqualified);
+        assert_relative_eq!(bot.fitness, bot.money - 75.0, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_breed_without_positive_fitness() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 10;
+        config.hamming = 0.0;
+
+        // every bot lost money or was disqualified so none of them earn tickets
+        let mut bots: Vec<Bot> = (0..10).map(|id| Bot::new(&config, id)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.fitness = -(index as f64);
+            bot.disqualified = index % 2 == 0;
+        }
+
+        let new_bots = simulation::breed(&bots, &config, 2, &mut 0);
+        assert_eq!(new_bots.len(), 10);
+    }
+
+    #[test]
+    fn test_island_topology() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 10;
+        config.islands.number_of_islands = 3;
+
+        assert_eq!(config.islands.island_sizes(10), vec!(4, 3, 3));
+        assert_eq!(config.islands.destinations(2), vec!(0));
+        assert!(config.islands.is_migration_generation(5));
+        assert!(!config.islands.is_migration_generation(6));
+
+        config.islands.topology = Topology::FullyConnected;
+        assert_eq!(config.islands.destinations(1), vec!(0, 2));
+        assert_eq!(config.islands.incoming_migrants(), 2);
+
+        // two migrants from each of the other islands would replace every bred bot of a three bot island
+        assert!(config.islands.validate(10, 0).is_empty());
+        config.islands.number_of_migrants = 2;
+        assert_eq!(config.islands.validate(10, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_island_stats() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 3;
+        config.islands.number_of_islands = 3;
+
+        let mut bots: Vec<Bot> = (0..3).map(|id| Bot::new(&config, id)).collect();
+        bots[0].fitness = -5.0;
+        bots[1].fitness = -3.0;
+        bots[2].fitness = 1.0;
+        bots[2].disqualified = true;
+        bots[2].island = 2;
+
+        // the best of an island that lost money is still its best bot, an island of no qualified bots has no fitness
+        let stats = config.islands.stats(&bots);
+        assert_relative_eq!(stats[0].best_fitness.unwrap(), -3.0);
+        assert_relative_eq!(stats[0].average_fitness.unwrap(), -4.0);
+        assert_eq!(stats[1].number_of_bots, 0);
+        assert_eq!(stats[1].best_fitness, None);
+        assert_eq!(stats[2].number_of_bots, 1);
+        assert_eq!(stats[2].best_fitness, None);
+        assert_eq!(stats[2].average_fitness, None);
+    }
+
+    #[test]
+    fn test_breed_islands_with_migration() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 12;
+        config.hamming = 0.0;
+        config.elite_bot_carry_over = 1;
+        config.islands.number_of_islands = 3;
+        config.islands.migration_interval = 5;
+        config.islands.number_of_migrants = 1;
+
+        let mut bots: Vec<Bot> = (0..12).map(|id| Bot::new(&config, id)).collect();
+        config.islands.assign(&mut bots);
+        for bot in bots.iter_mut() {
+            bot.fitness = 100.0 - bot.id as f64;
+        }
+
+        // no migration after generation 4
+        let new_bots = islands::breed_islands(bots, &config, 5, &mut 0);
+        assert_eq!(new_bots.len(), 12);
+        assert!(new_bots.iter().all(|bot| bot.migrated_from.is_none()));
+        assert!(new_bots.iter().enumerate().all(|(id, bot)| bot.id == id as u64 && bot.island == id as u64 / 4));
+
+        // after generation 5 the best bot of each island moves on to the next one around the ring
+        let mut bots = new_bots;
+        for bot in bots.iter_mut() {
+            bot.fitness = 100.0 - bot.id as f64;
+        }
+        let best_traits: Vec<Traits> = (0..3).map(|island| bots[island * 4].traits).collect();
+
+        let new_bots = islands::breed_islands(bots, &config, 6, &mut 0);
+        let stats = config.islands.stats(&new_bots);
+        for island in 0..3 {
+            let migrant = &new_bots[(island * 4) + 3];
+            let source = (island + 2) % 3;
+
+            assert_eq!(migrant.migrated_from, Some(source as u64));
+            assert_eq!(migrant.traits, best_traits[source]);
+            assert_eq!(stats[island].migrants, 1);
+            assert_eq!(stats[island].number_of_bots, 4);
+        }
+    }
+
+    #[test]
+    fn test_speciation_shares_fitness() {
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = 10.0 * (index + 1) as f64;
+        }
+
+        // the first bot is far from the rest
+        bots[0].traits.maximum_buy_momentum = 4.0;
+        bots[0].traits.number_of_averaging_periods = 10;
+        bots[1].fitness = -10.0;
+
+        let speciation = Speciation {
+            enabled: true,
+            ..Speciation::default()
+        };
+        let species = speciation::speciate(&mut bots, &speciation);
+
+        assert_eq!(species.len(), 2);
+        assert_eq!(species[0].representative, 3);
+        assert_eq!(species[0].number_of_bots, 3);
+        assert_eq!(species[1].representative, 0);
+        assert_eq!(species[1].number_of_bots, 1);
+
+        assert_eq!(bots[0].species, 1);
+        assert_relative_eq!(bots[0].selection_fitness(), 10.0, max_relative = 0.0001);
+        assert_relative_eq!(bots[3].selection_fitness(), 40.0 / 3.0, max_relative = 0.0001);
+        assert_relative_eq!(bots[1].selection_fitness(), -30.0, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_disqualified_bots_left_out_of_stats() {
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..3).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+        }
+
+        // every qualified bot lost money so the disqualified bot's 0 would beat them
+        bots[0].fitness = 0.0;
+        bots[0].disqualified = true;
+        bots[1].fitness = -5.0;
+        bots[2].fitness = -3.0;
+
+        let speciation = Speciation {
+            enabled: true,
+            ..Speciation::default()
+        };
+        let species = speciation::speciate(&mut bots, &speciation);
+        assert_eq!(species.len(), 1);
+        assert_eq!(species[0].representative, 2);
+        assert_eq!(species[0].number_of_bots, 3);
+        assert_relative_eq!(species[0].best_fitness.unwrap(), -3.0);
+        assert_relative_eq!(species[0].average_fitness.unwrap(), -4.0);
+        assert_relative_eq!(bots[2].selection_fitness(), -6.0, max_relative = 0.0001);
+
+        let mut progress = Progress::new();
+        progress.update(&bots);
+        assert_eq!(progress.evaluations, 3);
+        assert_relative_eq!(progress.best_fitness.unwrap(), -3.0);
+        assert_relative_eq!(progress.mean_fitness.unwrap(), -4.0);
+
+        // a generation of only disqualified bots is no improvement
+        for bot in bots.iter_mut() {
+            bot.disqualified = true;
+        }
+        progress.update(&bots);
+        assert_eq!(progress.evaluations, 6);
+        assert_eq!(progress.generations_without_improvement, 1);
+        assert_relative_eq!(progress.best_fitness.unwrap(), -3.0);
+    }
+
+    #[test]
+    fn test_breed_protects_small_species() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 6;
+        config.elite_bot_carry_over = 0;
+        config.speciation.enabled = true;
+        config.speciation.protected_species_size = 2;
+        config.selection.method = SelectionMethod::Truncation;
+        config.selection.truncation_percentage = 20.0;
+
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..6).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = 100.0 - index as f64;
+        }
+        bots[5].traits.maximum_buy_momentum = 4.0;
+        bots[5].traits.number_of_averaging_periods = 10;
+        speciation::speciate(&mut bots, &config.speciation);
+
+        // the last bot is never picked by truncation but its species of one still gets a child first
+        config.mutation_chance = 0.0;
+        let new_bots = simulation::breed(&bots, &config, 2, &mut 0);
+        assert_eq!(new_bots.len(), 6);
+        assert_eq!(new_bots[0].traits, bots[5].traits);
+    }
+
+    #[test]
+    fn test_breed_when_no_pair_passes_hamming() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 4;
+        config.hamming = 100.0;
+
+        // identical bots can never pass the threshold
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = 10.0;
+        }
+
+        let mut partner_fallbacks = 0;
+        let new_bots = simulation::breed(&bots, &config, 2, &mut partner_fallbacks);
+        assert_eq!(new_bots.len(), 4);
+        assert_eq!(partner_fallbacks, 4 - config.elite_bot_carry_over);
+    }
+
+    #[test]
+    fn test_diversity_intervention() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 10;
+        config.elite_bot_carry_over = 2;
+        config.diversity.threshold = 1.0;
+
+        // a population of copies has no diversity
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..10).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+        }
+        bots[9].migrated_from = Some(1);
+        assert_relative_eq!(diversity::diversity(&bots), 0.0);
+        assert!(config.diversity.check(0.0, 10).is_none());
+
+        config.diversity.enabled = true;
+        config.diversity.replacement_percentage = 100.0;
+        let intervention = config.diversity.check(0.0, 10).unwrap();
+        assert_eq!(intervention.number_of_bots, 10);
+
+        // only the bred bots can be replaced, the migrant that took the last bred slot is kept
+        let mut rng = rand::thread_rng();
+        let number_changed = diversity::intervene(&mut bots, &intervention, &config, 2, &mut rng);
+        assert_eq!(number_changed, 7);
+        assert_eq!(bots[0].traits, traits);
+        assert_eq!(bots[1].traits, traits);
+        assert_eq!(bots[9].traits, traits);
+        assert_eq!(bots[9].migrated_from, Some(1));
+        assert!(bots.iter().enumerate().all(|(id, bot)| bot.id == id as u64));
+        assert!(diversity::diversity(&bots) > 0.0);
+
+        config.diversity.action = DiversityAction::Hypermutation;
+        assert!(config.diversity.check(2.0, 10).is_none());
+        assert_eq!(config.diversity.check(0.5, 10).unwrap().action, DiversityAction::Hypermutation);
+    }
+
+    #[test]
+    fn test_hypermutation_stays_close_to_the_parent() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 100;
+        config.elite_bot_carry_over = 0;
+        config.mutation_chance = 0.05;
+        config.mutation.operator = MutationOperator::Gaussian;
+        config.mutation.sigma_percentage = 5.0;
+        config.diversity.enabled = true;
+        config.diversity.threshold = 1.0;
+        config.diversity.replacement_percentage = 100.0;
+        config.diversity.action = DiversityAction::Hypermutation;
+        config.diversity.hypermutation_chance = 1.0;
+
+        let parents: Vec<Bot> = (0..100).map(|id| Bot::new(&config, id)).collect();
+        let mut bots: Vec<Bot> = parents.iter().map(|bot| bot.create_clone(&config, bot.id)).collect();
+
+        let intervention = config.diversity.check(0.0, 100).unwrap();
+        let mut rng = rand::thread_rng();
+        assert_eq!(diversity::intervene(&mut bots, &intervention, &config, 2, &mut rng), 100);
+
+        // every gene moves a small step so the bots are further from their parents then normal mutation takes them
+        // but still much closer then a random bot would be
+        let hypermutated_distance: f64 = bots.iter().zip(&parents).map(|(bot, parent)| bot.hamming(parent)).sum::<f64>() / 100.0;
+        let mutated_distance: f64 = parents.iter().map(|parent| {
+            let mut mutated_traits = parent.traits;
+            Momentum::mutate(&mut mutated_traits, &mut rng, &config, 2);
+            Momentum::distance(&mutated_traits, &parent.traits)
+        }).sum::<f64>() / 100.0;
+        let random_distance: f64 = parents.iter().map(|parent| Bot::new(&config, 0).hamming(parent)).sum::<f64>() / 100.0;
+
+        assert!(hypermutated_distance > mutated_distance * 2.0);
+        assert!(hypermutated_distance < random_distance / 2.0);
+        assert!(bots.iter().zip(&parents).all(|(bot, parent)| bot.traits != parent.traits && bot.id == parent.id));
+    }
+
+    #[test]
+    fn test_stopping_criteria() {
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.fitness = index as f64;
+        }
+
+        let mut stopping_criteria = StoppingCriteria {
+            no_improvement_generations: Some(2),
+            target_fitness: Some(10.0),
+            evaluation_budget: Some(14),
+            ..StoppingCriteria::default()
+        };
+        let mut progress = Progress::new();
+
+        progress.update(&bots);
+        assert_eq!(progress.evaluations, 4);
+        assert_relative_eq!(progress.best_fitness.unwrap(), 3.0);
+        assert_relative_eq!(progress.mean_fitness.unwrap(), 1.5);
+        assert_eq!(stopping_criteria.check(&progress, 4), None);
+
+        // the same generation again is no improvement
+        progress.update(&bots);
+        progress.update(&bots);
+        assert_eq!(progress.generations_without_improvement, 2);
+        assert_eq!(stopping_criteria.check(&progress, 4), Some(StopReason::NoImprovement));
+
+        // another generation of four would go past the budget
+        stopping_criteria.no_improvement_generations = None;
+        assert_eq!(stopping_criteria.check(&progress, 4), Some(StopReason::EvaluationBudget));
+
+        bots[0].fitness = 12.0;
+        progress.update(&bots);
+        assert_eq!(progress.generations_without_improvement, 0);
+        assert_eq!(stopping_criteria.check(&progress, 4), Some(StopReason::TargetFitness));
+    }
+
+    #[test]
+    fn test_hall_of_fame() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 4;
+        config.elite_bot_carry_over = 1;
+        config.hall_of_fame.size = 2;
+        config.hall_of_fame.reinjected_bots = 2;
+
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = 10.0 * (index + 1) as f64;
+            bot.traits.percent_purchase = 10.0 * (index + 1) as f64;
+        }
+        bots[3].disqualified = true;
+
+        let mut hall_of_fame = HallOfFame::new();
+        hall_of_fame.update(&bots, 1, &config.hall_of_fame);
+        assert_eq!(hall_of_fame.entries.len(), 2);
+        assert_eq!(hall_of_fame.entries[0].bot_id, 2);
+        assert_eq!(hall_of_fame.entries[1].bot_id, 1);
+
+        // the same genome doing better replaces its old entry instead of taking a second place
+        bots[2].fitness = 50.0;
+        hall_of_fame.update(&bots, 2, &config.hall_of_fame);
+        assert_eq!(hall_of_fame.entries.len(), 2);
+        assert_eq!(hall_of_fame.entries[0].generation, 2);
+        assert_relative_eq!(hall_of_fame.entries[0].fitness, 50.0);
+
+        // genomes already in the population aren't put back and the elite bot is left alone
+        let mut new_bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in new_bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+        }
+        new_bots[0].traits = hall_of_fame.entries[0].traits;
+
+        let number_reinjected = hall_of_fame.reinject(&mut new_bots, &config);
+        assert_eq!(number_reinjected, 1);
+        assert_eq!(new_bots[3].traits, hall_of_fame.entries[1].traits);
+        assert_eq!(new_bots[3].id, 3);
+    }
+
+    #[test]
+    fn test_hall_of_fame_merges_close_genomes() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+
+        // three genomes next to each other and one far away
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..4).map(|_| generate_default_bot(traits)).collect();
+        for (index, (bot, fitness)) in bots.iter_mut().zip([10.0, 30.0, 20.0, 5.0]).enumerate() {
+            bot.id = index as u64;
+            bot.fitness = fitness;
+        }
+        bots[0].traits.percent_purchase = 10.0;
+        bots[1].traits.percent_purchase = 11.0;
+        bots[2].traits.percent_purchase = 12.0;
+        bots[3].traits.percent_purchase = 90.0;
+
+        let mut hall_of_fame = HallOfFame::new();
+        hall_of_fame.update(&bots[0..1], 1, &config.hall_of_fame);
+        hall_of_fame.update(&bots[2..4], 1, &config.hall_of_fame);
+        assert_eq!(hall_of_fame.entries.len(), 3);
+
+        // the middle genome is close to both of the others so all three are merged into the best of them
+        config.hall_of_fame.minimum_distance = Momentum::distance(&bots[1].traits, &bots[0].traits).max(Momentum::distance(&bots[1].traits, &bots[2].traits));
+        assert!(Momentum::distance(&bots[1].traits, &bots[3].traits) > config.hall_of_fame.minimum_distance);
+        hall_of_fame.update(&bots[1..2], 2, &config.hall_of_fame);
+        assert_eq!(hall_of_fame.entries.len(), 2);
+        assert_eq!(hall_of_fame.entries[0].bot_id, 1);
+        assert_relative_eq!(hall_of_fame.entries[0].fitness, 30.0);
+        assert_eq!(hall_of_fame.entries[1].bot_id, 3);
+
+        // a worse bot close to the best doesn't take its place
+        bots[0].fitness = 25.0;
+        hall_of_fame.update(&bots[0..1], 3, &config.hall_of_fame);
+        assert_eq!(hall_of_fame.entries.len(), 2);
+        assert_eq!(hall_of_fame.entries[0].bot_id, 1);
+    }
+
+    #[test]
+    fn test_hall_of_fame_leaves_migrants() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.number_of_bots = 12;
+        config.hamming = 0.0;
+        config.elite_bot_carry_over = 1;
+        config.islands.number_of_islands = 3;
+        config.islands.migration_interval = 5;
+        config.islands.number_of_migrants = 1;
+        config.hall_of_fame.size = 3;
+        config.hall_of_fame.reinjected_bots = 3;
+
+        let traits = generate_default_traits();
+        let mut hall_of_fame_bots: Vec<Bot> = (0..3).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in hall_of_fame_bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = 1000.0 - index as f64;
+            bot.traits.percent_purchase = (index + 1) as f64;
+        }
+        let mut hall_of_fame = HallOfFame::new();
+        hall_of_fame.update(&hall_of_fame_bots, 1, &config.hall_of_fame);
+
+        let mut bots: Vec<Bot> = (0..12).map(|id| Bot::new(&config, id)).collect();
+        config.islands.assign(&mut bots);
+        for bot in bots.iter_mut() {
+            bot.fitness = 100.0 - bot.id as f64;
+        }
+        let best_traits: Vec<Traits> = (0..3).map(|island| bots[island * 4].traits).collect();
+
+        // the migrants take the last slot of each island so the hall of fame bots go in the slot before
+        let mut new_bots = islands::breed_islands(bots, &config, 6, &mut 0);
+        let number_reinjected = hall_of_fame.reinject(&mut new_bots, &config);
+        assert_eq!(number_reinjected, 3);
+
+        for island in 0..3 {
+            let migrant = &new_bots[(island * 4) + 3];
+            assert_eq!(migrant.migrated_from, Some(((island + 2) % 3) as u64));
+            assert_eq!(migrant.traits, best_traits[(island + 2) % 3]);
+            assert_eq!(new_bots[(island * 4) + 2].traits, hall_of_fame.entries[island].traits);
+        }
+    }
+
+    #[test]
+    fn test_selection_methods_with_negative_fitness() {
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.selection.truncation_percentage = 20.0;
+
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..10).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = -(index as f64);
+        }
+
+        let mut rng = rand::thread_rng();
+        for method in [SelectionMethod::Roulette, SelectionMethod::Tournament, SelectionMethod::LinearRank, SelectionMethod::StochasticUniversalSampling, SelectionMethod::Truncation] {
+            config.selection.method = method;
+            let mut parent_selection = ParentSelection::new(&bots, &config);
+
+            for _ in 0..50 {
+                let parent = parent_selection.select(&mut rng);
+
+                // only the top 20% are bred from with truncation
+                if method == SelectionMethod::Truncation {
+                    assert!(parent.id < 2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stochastic_universal_sampling() {
+        let traits = generate_default_traits();
+        let mut bots: Vec<Bot> = (0..3).map(|_| generate_default_bot(traits)).collect();
+        for (index, bot) in bots.iter_mut().enumerate() {
+            bot.id = index as u64;
+            bot.fitness = (index as f64 * 10.0) - 10.0;
+        }
+        let bot_references: Vec<&Bot> = bots.iter().collect();
+
+        // shifted up by the worst fitness the shares are 0, 10 and 20 so three picks always land the same way
+        let mut rng = rand::thread_rng();
+        let picks = selection::stochastic_universal_sampling(&bot_references, 3, &mut rng);
+        let mut ids: Vec<u64> = picks.iter().map(|bot| bot.id).collect();
+        ids.sort();
+        assert_eq!(ids, vec!(1, 2, 2));
+    }
+
+    #[test]
+    fn test_blx_alpha_crossover() {
+        let crossover = Crossover { operator: CrossoverOperator::BlxAlpha, alpha: 0.5, ..Crossover::default() };
+        let mut rng = rand::thread_rng();
+
+        // the parents are 2 apart so children can land 1 past either of them
+        for _ in 0..100 {
+            let child = crossover.cross_f64(4.0, 6.0, 0.0, 100.0, &mut rng);
+            assert!((3.0..=7.0).contains(&child));
+
+            // and are clamped to the bounds of the gene
+            let child = crossover.cross_f64(4.0, 6.0, 4.0, 6.0, &mut rng);
+            assert!((4.0..=6.0).contains(&child));
+        }
+    }
+
+    #[test]
+    fn test_sbx_crossover() {
+        let crossover = Crossover { operator: CrossoverOperator::Sbx, distribution_index: 2.0, ..Crossover::default() };
+        let mut rng = rand::thread_rng();
+
+        // the children are spread evenly around the middle of the parents
+        assert_relative_eq!(crossover.cross_f64(5.0, 5.0, 0.0, 10.0, &mut rng), 5.0);
+        for _ in 0..100 {
+            let child = crossover.cross_f64(4.0, 6.0, 0.0, 10.0, &mut rng);
+            assert!((0.0..=10.0).contains(&child));
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_crossover() {
+        let crossover = Crossover { operator: CrossoverOperator::Arithmetic, blend_weight: Some(0.25), ..Crossover::default() };
+        let mut rng = rand::thread_rng();
+
+        assert_relative_eq!(crossover.cross_f64(4.0, 8.0, 0.0, 10.0, &mut rng), 7.0);
+        // whole number genes are rounded
+        assert_eq!(crossover.cross_u64(5, 10, 1, 20, &mut rng), 9);
+    }
+
+    #[test]
+    fn test_breed_with_arithmetic_crossover() {
+        let traits = generate_default_traits();
+        let bot_one = generate_default_bot(traits);
+        let mut bot_two = generate_default_bot(traits);
+        bot_two.traits.number_of_averaging_periods = 10;
+        bot_two.traits.percent_purchase = 50.0;
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.mutation_chance = 0.0;
+        config.crossover.operator = CrossoverOperator::Arithmetic;
+        config.crossover.blend_weight = Some(0.5);
+        let mut rng = rand::thread_rng();
+
+        // the baby is half way between its parents instead of a copy of one of them
+        let baby_bot = bot_one.breed(&bot_two, &mut rng, &config, 5, 2);
+        assert_eq!(baby_bot.traits.number_of_averaging_periods, ((traits.number_of_averaging_periods as f64 + 10.0) / 2.0).round() as u64);
+        assert_relative_eq!(baby_bot.traits.percent_purchase, (traits.percent_purchase + 50.0) / 2.0);
+    }
+
+    #[test]
+    fn test_mutation_annealing() {
+        let mutation = Mutation { annealing_rate: 0.5, minimum_annealing_scale: 0.2, ..Mutation::default() };
+
+        assert_relative_eq!(mutation.annealing_scale(1), 1.0);
+        assert_relative_eq!(mutation.annealing_scale(2), 0.5);
+        assert_relative_eq!(mutation.annealing_scale(10), 0.2);
+        assert_relative_eq!(mutation.step_size(2), 0.05);
+    }
+
+    #[test]
+    fn test_gaussian_mutation_clamped() {
+        let mutation = Mutation { operator: MutationOperator::Gaussian, ..Mutation::default() };
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let value = mutation.mutate_f64(9.5, 0.0, 10.0, 0.5, &mut rng);
+            assert!((0.0..=10.0).contains(&value));
+
+            let value = mutation.mutate_u64(1, 1, 30, 0.5, &mut rng);
+            assert!((1..=30).contains(&value));
+        }
+
+        // with no step the gene stays where it is
+        assert_relative_eq!(mutation.mutate_f64(5.0, 0.0, 10.0, 0.0, &mut rng), 5.0);
+    }
+
+    #[test]
+    fn test_self_adaptive_step_sizes() {
+        let mutation = Mutation { operator: MutationOperator::SelfAdaptive, minimum_step_percentage: 1.0, ..Mutation::default() };
+        let mut rng = rand::thread_rng();
+
+        // genomes without step sizes start from the sigma before they adapt
+        let mut step_sizes = [0.0; NUMBER_OF_STEP_SIZES];
+        for _ in 0..100 {
+            mutation.adapt_step_sizes(&mut step_sizes, &mut rng);
+            assert!(step_sizes.iter().all(|step_size| (0.01..=1.0).contains(step_size)));
+        }
+    }
+
+    #[test]
+    fn test_breed_with_gaussian_mutation() {
+        let traits = generate_default_traits();
+        let bot_one = generate_default_bot(traits);
+        let bot_two = generate_default_bot(traits);
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.mutation_chance = 1.0;
+        config.mutation.operator = MutationOperator::SelfAdaptive;
+        let mut rng = rand::thread_rng();
+
+        // every gene moves but stays inside its range and the step sizes mutate along with it
+        let baby_bot = bot_one.breed(&bot_two, &mut rng, &config, 5, 2);
+        assert!(baby_bot.traits.percent_purchase >= config.traits.percent_purchase.min);
+        assert!(baby_bot.traits.percent_purchase <= config.traits.percent_purchase.max);
+        assert!(baby_bot.traits.number_of_averaging_periods >= config.traits.number_of_averaging_periods.min);
+        assert!(baby_bot.traits.number_of_averaging_periods <= config.traits.number_of_averaging_periods.max);
+        assert_ne!(baby_bot.traits.step_sizes, traits.step_sizes);
+    }
+
+    #[test]
+    fn test_hamming_no_difference() {
+        let traits = generate_default_traits();
+        let bot_one = generate_default_bot(traits);
+        let bot_two = generate_default_bot(traits);
+
+        let hamming_value = bot_one.hamming(&bot_two);
+        assert_relative_eq!(hamming_value, 0.0, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_hamming_difference() {
+        let traits = generate_default_traits();
+        let bot_one = generate_default_bot(traits);
+        let mut bot_two = generate_default_bot(traits);
+
+        bot_two.traits.maximum_buy_momentum = 4.0;
+        bot_two.traits.number_of_averaging_periods = 10;
+
+        let hamming_value = bot_one.hamming(&bot_two);
+        assert_relative_eq!(hamming_value, 25.589, max_relative = 0.0001);
+    }
+
+    #[test]
+    fn test_breed_without_mutation() {
+        let traits = generate_default_traits();
+        let bot_one = generate_default_bot(traits);
+        let mut bot_two = generate_default_bot(traits);
+        bot_two.traits.number_of_averaging_periods = 10;
+        bot_two.traits.percent_purchase = 50.0;
+
+        let mut config = Arc::try_unwrap(generate_default_config()).unwrap();
+        config.mutation_chance = 0.0;
+
+        let mut rng = rand::thread_rng();
+        let baby_bot = bot_one.breed(&bot_two, &mut rng, &config, 5, 2);
+
+        assert_eq!(baby_bot.id, 5);
+        assert!(baby_bot.traits.number_of_averaging_periods == 1 || baby_bot.traits.number_of_averaging_periods == 10);
+        assert!(baby_bot.traits.percent_purchase == 90.0 || baby_bot.traits.percent_purchase == 50.0);
+        assert_relative_eq!(baby_bot.traits.stop_loss, 1.0, max_relative = 0.0001);
+        assert_relative_eq!(baby_bot.money, config.starting_money, max_relative = 0.0001);
     }
 }